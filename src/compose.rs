@@ -0,0 +1,86 @@
+/// The dead-key sequences we understand, for terminals that pass the keys of a
+/// sequence through one at a time rather than composing them first. Each dead
+/// key followed by one of its base characters makes the matching accented
+/// character:
+///
+/// | dead key | base characters | composed |
+/// |----------|-----------------|----------|
+/// | `'`      | aeiouy          | áéíóúý   |
+/// | `` ` ``  | aeiou           | àèìòù    |
+/// | `^`      | aeiou           | âêîôû    |
+/// | `"`      | aeiouy          | äëïöüÿ   |
+/// | `~`      | ano             | ãñõ      |
+/// | `,`      | c               | ç        |
+///
+/// along with the capitals of each (except `ÿ`).
+const SEQUENCES: [(char, &str, &str); 6] = [
+    ('\'', "aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+    ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('"', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+    ('~', "anoANO", "ãñõÃÑÕ"),
+    (',', "cC", "çÇ"),
+];
+
+fn compose(dead: char, base: char) -> Option<char> {
+    let (_, bases, composed) = SEQUENCES.iter().find(|(key, _, _)| *key == dead)?;
+    let idx = bases.chars().position(|c| c == base)?;
+
+    composed.chars().nth(idx)
+}
+
+/// Whether `c` could be the dead key of a sequence composing `expected`.
+fn starts(c: char, expected: char) -> bool {
+    SEQUENCES
+        .iter()
+        .any(|(key, _, composed)| *key == c && composed.contains(expected))
+}
+
+/// Turns dead-key sequences into the characters they compose. Dead keys are
+/// ordinary characters too, so one is only held back when the character it
+/// could start is the one that's expected next.
+#[derive(Debug, Default)]
+pub(crate) struct Composer {
+    pending: Option<char>,
+}
+
+impl Composer {
+    /// Feed in a typed character, returning the characters to pass on: none
+    /// if it might start a sequence, otherwise what's been typed so far.
+    pub fn feed(&mut self, c: char, expected: Option<char>) -> Vec<char> {
+        match self.pending.take() {
+            Some(dead) => match compose(dead, c) {
+                Some(composed) => vec![composed],
+                None => vec![dead, c],
+            },
+            None if expected.is_some_and(|expected| starts(c, expected)) => {
+                self.pending = Some(c);
+                vec![]
+            }
+            None => vec![c],
+        }
+    }
+
+    /// Forget a half-typed sequence.
+    pub fn cancel(&mut self) -> bool {
+        self.pending.take().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composer() {
+        let mut composer = Composer::default();
+
+        assert_eq!(composer.feed('\'', Some('é')), vec![]);
+        assert_eq!(composer.feed('e', Some('é')), vec!['é']);
+        // Only held back when the sequence is expected.
+        assert_eq!(composer.feed('\'', Some('s')), vec!['\'']);
+        // A sequence that doesn't compose passes both keys through.
+        assert_eq!(composer.feed('~', Some('ñ')), vec![]);
+        assert_eq!(composer.feed('x', Some('ñ')), vec!['~', 'x']);
+    }
+}
@@ -0,0 +1,388 @@
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    hands::Finger,
+    keyboard::{Mirror, NumberRow},
+    theme::ThemeName,
+    wordlist::Difficulty,
+};
+
+/// What's typed between words.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Delimiter {
+    Space,
+    /// One word per line, typed with Enter, for drills like code.
+    Newline,
+}
+
+impl Delimiter {
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Space => ' ',
+            Self::Newline => '\n',
+        }
+    }
+}
+
+/// What brings a test to an end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TestMode {
+    /// Typing this many words.
+    WordCount(usize),
+    /// This long passing since the first keystroke, with more words picked
+    /// as they run low.
+    Timed(Duration),
+}
+
+/// The settings a test was taken with, saved with its results so that
+/// exported sessions can be told apart and compared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Settings {
+    pub layout: String,
+    pub number_row: NumberRow,
+    /// How many words the test had.
+    pub words: usize,
+    pub delimiter: Delimiter,
+    pub difficulty: Difficulty,
+    pub target_accuracy: f64,
+}
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Learn to touch type using the Dvorak layout")]
+pub(crate) struct Config {
+    /// Delete the saved personal bests, resume point, weak words, review
+    /// schedule and history and exit
+    #[arg(long)]
+    pub reset: bool,
+
+    /// Pick up the course from the lesson the previous session was quit during
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Assemble each lesson's words into capitalised, punctuated sentences
+    #[arg(long)]
+    pub sentences: bool,
+
+    /// Read lesson alphabets from this file, one per line
+    #[arg(long, value_name = "PATH")]
+    pub lessons: Option<PathBuf>,
+
+    /// Check a lesson file against the word list and exit
+    #[arg(long, value_name = "PATH")]
+    pub validate_lessons: Option<PathBuf>,
+
+    /// Practise words from this file instead of the built-in list
+    #[arg(long, value_name = "PATH")]
+    pub word_list: Option<PathBuf>,
+
+    /// Use the small compiled-in word list rather than the full one
+    #[arg(long, conflicts_with = "word_list")]
+    pub minimal_words: bool,
+
+    /// Type the whole word list in file order, rather than sampled lessons
+    #[arg(long, requires = "word_list", conflicts_with = "sentences")]
+    pub ordered: bool,
+
+    /// Type random quotes instead of lessons, a new one each time Enter is pressed
+    #[arg(long, conflicts_with_all = ["ordered", "sentences"])]
+    pub quotes: bool,
+
+    /// Practise the words that went worst last session, instead of lessons
+    #[arg(long, conflicts_with_all = ["ordered", "quotes"])]
+    pub drill_weak: bool,
+
+    /// Review the words from the word list that are due, plus a few new ones,
+    /// bringing back the ones with typos sooner, instead of lessons
+    #[arg(long, conflicts_with_all = ["ordered", "quotes", "drill_weak", "finger", "course"])]
+    pub review: bool,
+
+    /// Type the Nth of the built-in benchmark passages, the same for everyone,
+    /// instead of lessons
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        conflicts_with_all = ["ordered", "quotes", "drill_weak", "finger", "course", "review"]
+    )]
+    pub benchmark: Option<usize>,
+
+    /// Work through the lessons in order, one after the other, instead of
+    /// picking each from the menu
+    #[arg(long, conflicts_with_all = ["ordered", "quotes", "drill_weak", "finger"])]
+    pub course: bool,
+
+    /// Practise only the keys typed by this finger, instead of lessons
+    #[arg(long, value_enum, conflicts_with_all = ["ordered", "quotes", "drill_weak"])]
+    pub finger: Option<Finger>,
+
+    /// Practise the shifted symbols of the number row, one hand then both,
+    /// instead of lessons
+    #[arg(
+        long,
+        conflicts_with_all = ["ordered", "quotes", "drill_weak", "finger", "course", "review"]
+    )]
+    pub symbols: bool,
+
+    /// Print the words each lesson would be typed with, picked just as they
+    /// would be for the test, instead of taking the tests
+    #[arg(
+        long,
+        conflicts_with_all = ["ordered", "quotes", "drill_weak", "finger", "review", "symbols"]
+    )]
+    pub print_words: bool,
+
+    /// Type capitals as lowercase letters, unless a capital's expected, so that
+    /// a stray shift or caps lock doesn't count as a typo
+    #[arg(long)]
+    pub ignore_case: bool,
+
+    /// Show correct keystrokes that took longer than this in their own colour
+    #[arg(long, value_name = "MS")]
+    pub slow_ms: Option<u64>,
+
+    /// Show correct keystrokes slower than --target-wpm in their own colour
+    #[arg(long, requires = "target_wpm", conflicts_with = "slow_ms")]
+    pub slow_keys: bool,
+
+    /// Show the letters of the current word as blanks until they're typed
+    #[arg(long, conflicts_with = "stacked")]
+    pub blank: bool,
+
+    /// Keep the word list's line breaks, typing Enter at the end of each line
+    #[arg(long, requires = "ordered")]
+    pub multiline: bool,
+
+    /// Save each test's results to this file as JSON
+    #[arg(long, value_name = "PATH")]
+    pub export: Option<PathBuf>,
+
+    /// Append any error that stops dvors, with what caused it, to this file
+    #[arg(long, value_name = "PATH")]
+    pub log_errors: Option<PathBuf>,
+
+    /// Add a row to this CSV file after each test, to chart progress over time
+    #[arg(long, value_name = "PATH")]
+    pub history: Option<PathBuf>,
+
+    /// Save an SVG keyboard coloured by each key's error rate over the lessons
+    #[arg(long, value_name = "PATH")]
+    pub export_heatmap: Option<PathBuf>,
+
+    /// Print the statistics of a session saved with --export and exit
+    #[arg(long, value_name = "PATH")]
+    pub analyze: Option<PathBuf>,
+
+    /// Print --analyze statistics as JSON
+    #[arg(long, requires = "analyze")]
+    pub json: bool,
+
+    /// Whether to lean towards common or rare words, among those each lesson's
+    /// keys can type
+    #[arg(long, value_enum, default_value = "normal")]
+    pub difficulty: Difficulty,
+
+    /// Add the apostrophe to every lesson, mixing in contractions like "don't"
+    #[arg(long)]
+    pub contractions: bool,
+
+    /// How many words each lesson has
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    pub words: usize,
+
+    /// Don't use any word twice in a test, as long as there are enough
+    /// different words to go round
+    #[arg(long)]
+    pub no_repeats: bool,
+
+    /// Keep picking more words for each lesson as they run out, until Esc is
+    /// pressed (or the time's up)
+    #[arg(long, conflicts_with = "sentences")]
+    pub endless: bool,
+
+    /// Order each lesson's words from easiest to hardest, instead of at random
+    #[arg(long)]
+    pub ramp: bool,
+
+    /// Only practise words with at least this many letters
+    #[arg(long, value_name = "N")]
+    pub min_len: Option<usize>,
+
+    /// Only practise words with at most this many letters
+    #[arg(long, value_name = "N")]
+    pub max_len: Option<usize>,
+
+    /// Mark this speed on the per-word wpm graph
+    #[arg(long, value_name = "WPM")]
+    pub target_wpm: Option<u64>,
+
+    /// Leave words with typos out of the per-word wpm graph
+    #[arg(long)]
+    pub clean_sparkline: bool,
+
+    /// Show the results of each test as a single line instead of the grid
+    #[arg(long)]
+    pub compact_results: bool,
+
+    /// Print a one-line summary of each test on exit, for logging
+    #[arg(long)]
+    pub print_results: bool,
+
+    /// Only count a lesson as mastered when every word is typed without a
+    /// single typo at --target-wpm or faster
+    #[arg(long, requires = "target_wpm")]
+    pub mastery: bool,
+
+    /// Give each word as much of the per-word wpm graph as the time it took
+    #[arg(long)]
+    pub time_axis: bool,
+
+    /// Leave the first keystroke, which is timed from the start of the test
+    /// rather than from a previous key, out of the test's time and wpm
+    #[arg(long)]
+    pub skip_first_keystroke: bool,
+
+    /// Show the live accuracy in green at or above this percentage, red below
+    #[arg(long, value_name = "PCT", default_value_t = 95.0)]
+    pub target_accuracy: f64,
+
+    /// End each test this many seconds after the first keystroke, counting
+    /// down the time left as it goes, with lessons picking more words as they
+    /// run out
+    #[arg(long, value_name = "SECS")]
+    pub time: Option<u64>,
+
+    /// Retype the tests of a session saved with --export, racing the ghost of
+    /// how they were typed the first time
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "ordered", "quotes", "drill_weak", "finger", "review", "benchmark", "symbols",
+            "course", "print_words", "pace", "warmup"
+        ]
+    )]
+    pub race: Option<PathBuf>,
+
+    /// Race a ghost that types at this speed
+    #[arg(long, value_name = "WPM")]
+    pub pace: Option<u64>,
+
+    /// Skip the current word with Esc, only quitting on a second Esc within
+    /// this many milliseconds
+    #[arg(long, value_name = "MS")]
+    pub double_esc: Option<u64>,
+
+    /// What the number row types without shift
+    #[arg(long, value_enum, default_value = "digits")]
+    pub number_row: NumberRow,
+
+    /// Mirror this half of the keyboard onto the other, for typing one handed,
+    /// leaving the other half's keys out of the lessons
+    #[arg(long, value_enum, value_name = "HALF")]
+    pub mirror: Option<Mirror>,
+
+    /// Show the keyboard, lighting up each key as it's pressed, until Esc
+    #[arg(long)]
+    pub show_keyboard: bool,
+
+    /// What to type between words
+    #[arg(long, value_enum, default_value = "space")]
+    pub delimiter: Delimiter,
+
+    /// Show only the word being typed, hiding the words before and after it
+    #[arg(long)]
+    pub hide_preview: bool,
+
+    /// Move on to the next word as soon as one's typed, without a space
+    #[arg(long)]
+    pub no_space: bool,
+
+    /// Type these words before each test, leaving them out of the results
+    #[arg(long, value_name = "WORDS")]
+    pub warmup: Option<String>,
+
+    /// Show which finger and row type the next character
+    #[arg(long)]
+    pub hints: bool,
+
+    /// Show the keyboard under the words, lighting up the next key to press in
+    /// green and the last one pressed in red
+    #[arg(long)]
+    pub keyboard: bool,
+
+    /// Blink the cursor marking the next character to type
+    #[arg(long)]
+    pub blink_cursor: bool,
+
+    /// Show the word being typed above what's been typed of it, instead of inline
+    #[arg(long, conflicts_with = "wrap")]
+    pub stacked: bool,
+
+    /// Wrap the whole text instead of keeping the current line in the middle
+    #[arg(long)]
+    pub wrap: bool,
+
+    /// Dim everything but the word being typed
+    #[arg(long)]
+    pub focus: bool,
+
+    /// Show results straight away, without filling the accuracy gauge
+    #[arg(long)]
+    pub no_animation: bool,
+
+    /// The colour theme to use
+    #[arg(long, value_enum, default_value = "default")]
+    pub theme: ThemeName,
+}
+
+impl Config {
+    /// The settings for a test of `words` words.
+    pub fn settings(&self, words: usize) -> Settings {
+        Settings {
+            layout: "dvorak".to_string(),
+            number_row: self.number_row,
+            words,
+            delimiter: self.delimiter,
+            difficulty: self.difficulty,
+            target_accuracy: self.target_accuracy,
+        }
+    }
+
+    /// Whether lessons are picked from the menu, which they are unless they're
+    /// taken as a course, as resuming one is.
+    pub fn menu(&self) -> bool {
+        !self.course && !self.resume
+    }
+
+    /// How a test of `words` words ends, which with `--time` is when the time's
+    /// up however many words that takes.
+    pub fn test_mode(&self, words: usize) -> TestMode {
+        match self.time {
+            Some(secs) => TestMode::Timed(Duration::from_secs(secs)),
+            None => TestMode::WordCount(words),
+        }
+    }
+
+    /// How long a correct keystroke can take before it's shown as slow, from
+    /// `--slow-ms` or the time a keystroke takes at `--target-wpm` with
+    /// `--slow-keys`.
+    pub fn slow_after(&self) -> Option<Duration> {
+        match (self.slow_ms, self.target_wpm) {
+            (Some(ms), _) => Some(Duration::from_millis(ms)),
+            (None, Some(wpm)) if self.slow_keys && wpm > 0 => {
+                Some(Duration::from_secs_f64(60.0 / (wpm as f64 * 5.0)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The word lengths allowed by `--min-len` and `--max-len`.
+    pub fn word_lengths(&self) -> RangeInclusive<usize> {
+        self.min_len.unwrap_or(0)..=self.max_len.unwrap_or(usize::MAX)
+    }
+}
@@ -0,0 +1,190 @@
+use std::ops::RangeInclusive;
+
+use clap::ValueEnum;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::heatmap;
+
+/// Which hand types a key when touch typing on a Dvorak layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Hand {
+    Left,
+    Right,
+}
+
+/// The hand that types `c`, or `None` for characters off the main keys (like
+/// space, which is for the thumbs).
+pub(crate) fn hand(c: char) -> Option<Hand> {
+    match c.to_ascii_lowercase() {
+        '`' | '~' | '1' | '!' | '2' | '@' | '3' | '#' | '4' | '$' | '5' | '%' | '\'' | '"'
+        | ',' | '<' | '.' | '>' | 'p' | 'y' | 'a' | 'o' | 'e' | 'u' | 'i' | ';' | ':' | 'q'
+        | 'j' | 'k' | 'x' => Some(Hand::Left),
+        '6' | '^' | '7' | '&' | '8' | '*' | '9' | '(' | '0' | ')' | '[' | '{' | ']' | '}' | 'f'
+        | 'g' | 'c' | 'r' | 'l' | '/' | '?' | '=' | '+' | '\\' | '|' | 'd' | 'h' | 't' | 'n'
+        | 's' | '-' | '_' | 'b' | 'm' | 'w' | 'v' | 'z' => Some(Hand::Right),
+        _ => None,
+    }
+}
+
+/// Which finger types a key when touch typing on a Dvorak layout.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+pub(crate) const FINGERS: [Finger; 8] = [
+    Finger::LeftPinky,
+    Finger::LeftRing,
+    Finger::LeftMiddle,
+    Finger::LeftIndex,
+    Finger::RightIndex,
+    Finger::RightMiddle,
+    Finger::RightRing,
+    Finger::RightPinky,
+];
+
+impl Finger {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LeftPinky => "left pinky",
+            Self::LeftRing => "left ring",
+            Self::LeftMiddle => "left middle",
+            Self::LeftIndex => "left index",
+            Self::RightIndex => "right index",
+            Self::RightMiddle => "right middle",
+            Self::RightRing => "right ring",
+            Self::RightPinky => "right pinky",
+        }
+    }
+
+    /// The home row key the finger rests on.
+    pub fn home(self) -> char {
+        match self {
+            Self::LeftPinky => 'a',
+            Self::LeftRing => 'o',
+            Self::LeftMiddle => 'e',
+            Self::LeftIndex => 'u',
+            Self::RightIndex => 'h',
+            Self::RightMiddle => 't',
+            Self::RightRing => 'n',
+            Self::RightPinky => 's',
+        }
+    }
+
+    /// The unshifted characters on the keys this finger types.
+    pub fn keys(self) -> &'static str {
+        match self {
+            Self::LeftPinky => "`1'a;",
+            Self::LeftRing => "2,oq",
+            Self::LeftMiddle => "3.ej",
+            Self::LeftIndex => "45pyuikx",
+            Self::RightIndex => "67fgdhbm",
+            Self::RightMiddle => "8ctw",
+            Self::RightRing => "9rnv",
+            Self::RightPinky => "0[]l/=\\s-z",
+        }
+    }
+}
+
+/// The finger that types `c`, or `None` for characters off the main keys.
+pub(crate) fn finger(c: char) -> Option<Finger> {
+    let key = heatmap::unshifted(c);
+    FINGERS
+        .iter()
+        .copied()
+        .find(|finger| finger.keys().contains(key))
+}
+
+/// Make up `amount` strings of this finger's keys, for when there aren't
+/// enough real words that can be typed with it alone. They're as long as
+/// `sequences` makes them.
+pub(crate) fn finger_sequences<R: Rng>(
+    finger: Finger,
+    lengths: &RangeInclusive<usize>,
+    amount: usize,
+    rng: &mut R,
+) -> Vec<String> {
+    sequences(finger.keys(), lengths, amount, rng)
+}
+
+/// Make up `amount` strings of random `keys`, for practising keys that real
+/// words don't use (or don't use enough). They're as short as `lengths`
+/// allows, but no shorter than three keys unless `lengths` stops short of
+/// that, as with `--max-len 2`.
+pub(crate) fn sequences<R: Rng>(
+    keys: &str,
+    lengths: &RangeInclusive<usize>,
+    amount: usize,
+    rng: &mut R,
+) -> Vec<String> {
+    let keys = keys.chars().collect::<Vec<char>>();
+    let len = (*lengths.start()).max(3).min(*lengths.end());
+
+    (0..amount)
+        .map(|_| (0..len).map(|_| *keys.choose(rng).unwrap()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hand() {
+        assert_eq!(hand('A'), Some(Hand::Left));
+        assert_eq!(hand('>'), Some(Hand::Left));
+        assert_eq!(hand('s'), Some(Hand::Right));
+        assert_eq!(hand('_'), Some(Hand::Right));
+        assert_eq!(hand(' '), None);
+    }
+
+    #[test]
+    fn test_finger_keys() {
+        for finger in FINGERS {
+            let expected = if format!("{:?}", finger).starts_with("Left") {
+                Hand::Left
+            } else {
+                Hand::Right
+            };
+            for c in finger.keys().chars() {
+                assert_eq!(hand(c), Some(expected), "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_finger() {
+        assert_eq!(finger('t'), Some(Finger::RightMiddle));
+        assert_eq!(finger('U'), Some(Finger::LeftIndex));
+        assert_eq!(finger('_'), Some(Finger::RightPinky));
+        assert_eq!(finger(' '), None);
+    }
+
+    #[test]
+    fn test_finger_sequences() {
+        let sequences = finger_sequences(Finger::RightPinky, &(0..=2), 20, &mut rand::thread_rng());
+        assert_eq!(sequences.len(), 20);
+        for sequence in sequences {
+            assert_eq!(sequence.chars().count(), 2);
+            assert!(sequence
+                .chars()
+                .all(|c| Finger::RightPinky.keys().contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_sequences_length() {
+        let len = |lengths| sequences("aoeu", &lengths, 1, &mut rand::thread_rng())[0].len();
+
+        assert_eq!(len(0..=8), 3);
+        assert_eq!(len(5..=8), 5);
+        assert_eq!(len(1..=2), 2);
+    }
+}
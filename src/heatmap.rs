@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+/// The keys of a Dvorak keyboard, row by row from the number row down, by
+/// their unshifted characters.
+pub(crate) const ROWS: [&str; 4] = [
+    "`1234567890[]",
+    "',.pyfgcrl/=\\",
+    "aoeuidhtns-",
+    ";qjkxbmwvz",
+];
+
+const ROW_NAMES: [&str; 4] = ["number row", "top row", "home row", "bottom row"];
+
+/// How far each row is indented from the one above, in key widths, as on a
+/// staggered keyboard.
+const ROW_OFFSETS: [f64; 4] = [0.0, 1.5, 1.75, 2.25];
+
+const KEY_SIZE: f64 = 40.0;
+const KEY_GAP: f64 = 4.0;
+
+/// How many times each key was expected, and how many of those times it was
+/// mistyped, keyed by the key's unshifted character.
+pub(crate) type KeyStats = BTreeMap<char, (usize, usize)>;
+
+/// The unshifted character on the key that types `c`.
+pub(crate) fn unshifted(c: char) -> char {
+    match c {
+        '~' => '`',
+        '!' => '1',
+        '@' => '2',
+        '#' => '3',
+        '$' => '4',
+        '%' => '5',
+        '^' => '6',
+        '&' => '7',
+        '*' => '8',
+        '(' => '9',
+        ')' => '0',
+        '{' => '[',
+        '}' => ']',
+        '"' => '\'',
+        '<' => ',',
+        '>' => '.',
+        '?' => '/',
+        '+' => '=',
+        '|' => '\\',
+        '_' => '-',
+        ':' => ';',
+        _ => c.to_ascii_lowercase(),
+    }
+}
+
+/// The character the key that types `c` types with shift, or `c` itself if
+/// shift makes no difference.
+pub(crate) fn shifted(c: char) -> char {
+    ('!'..='~')
+        .find(|shifted| *shifted != c && unshifted(*shifted) == c)
+        .unwrap_or(c)
+}
+
+/// Which of the `ROWS` the key that types `c` is on, if it's on the keyboard.
+pub(crate) fn row(c: char) -> Option<usize> {
+    let key = unshifted(c);
+    ROWS.iter().position(|row| row.contains(key))
+}
+
+/// Where the key that types `c` is on the keyboard, in key widths across from
+/// the left of the number row and rows down from it, if it's on the keyboard.
+pub(crate) fn position(c: char) -> Option<(f64, f64)> {
+    let key = unshifted(c);
+    let row = row(c)?;
+    let col = ROWS[row].chars().position(|k| k == key)?;
+
+    Some((col as f64 + ROW_OFFSETS[row], row as f64))
+}
+
+/// The name of the row of the key that types `c`, if it's on the keyboard.
+pub(crate) fn row_name(c: char) -> Option<&'static str> {
+    row(c).map(|idx| ROW_NAMES[idx])
+}
+
+/// Colour an error rate from green (no errors) through yellow to red (half
+/// or more of the key's presses were wrong).
+fn heat(error_rate: f64) -> (u8, u8, u8) {
+    let heat = (error_rate * 2.0).clamp(0.0, 1.0);
+    if heat < 0.5 {
+        ((heat * 2.0 * 255.0) as u8, 200, 0)
+    } else {
+        (255, ((1.0 - heat) * 2.0 * 200.0) as u8, 0)
+    }
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        '\'' => "&apos;".to_string(),
+        '"' => "&quot;".to_string(),
+        _ => c.to_string(),
+    }
+}
+
+/// Draw the keyboard as an SVG, with each key coloured by its error rate and
+/// keys that weren't typed left grey.
+pub(crate) fn to_svg(stats: &KeyStats) -> String {
+    let pitch = KEY_SIZE + KEY_GAP;
+    let width = (ROWS[1].len() as f64 + ROW_OFFSETS[1]) * pitch + KEY_GAP;
+    let height = ROWS.len() as f64 * pitch + KEY_GAP;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\">\n",
+        width, height
+    );
+    for (row_idx, row) in ROWS.iter().enumerate() {
+        for (key_idx, key) in row.chars().enumerate() {
+            let x = KEY_GAP + (key_idx as f64 + ROW_OFFSETS[row_idx]) * pitch;
+            let y = KEY_GAP + row_idx as f64 * pitch;
+            let (fill, title) = match stats.get(&key) {
+                Some((total, typos)) if *total > 0 => {
+                    let (r, g, b) = heat(*typos as f64 / *total as f64);
+                    (
+                        format!("rgb({},{},{})", r, g, b),
+                        format!("{} of {} wrong", typos, total),
+                    )
+                }
+                _ => ("#ccc".to_string(), "not typed".to_string()),
+            };
+            svg.push_str(&format!(
+                "  <g><title>{}</title><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\"/>\
+                 <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text></g>\n",
+                title,
+                x,
+                y,
+                KEY_SIZE,
+                KEY_SIZE,
+                fill,
+                x + KEY_SIZE / 2.0,
+                y + KEY_SIZE / 2.0 + 5.0,
+                escape(key)
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        let mut stats = KeyStats::new();
+        stats.insert('a', (4, 0));
+        stats.insert(unshifted('"'), (2, 2));
+
+        let svg = to_svg(&stats);
+
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 47);
+        assert!(svg.contains("<title>0 of 4 wrong</title>"));
+        assert!(svg.contains("fill=\"rgb(0,200,0)\""));
+        assert!(svg.contains("<title>2 of 2 wrong</title>"));
+        assert!(svg.contains(">&apos;</text>"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+};
+
+use fehler::throws;
+use serde::{Deserialize, Serialize};
+
+use crate::{records, typingtest::TestResults, Error};
+
+const HEADER: &str = "date,lesson,wpm,accuracy,typos,seconds";
+
+const HISTORY_FILE: &str = "history.json";
+
+/// A lesson's test as it's kept in the history in the data directory.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionRecord {
+    /// Seconds since the Unix epoch.
+    timestamp: u64,
+    /// The lesson's alphabet, which stays the same when lessons are reordered
+    /// or replaced, or the flag a test in another mode was taken with.
+    lesson: String,
+    wpm: f64,
+    accuracy: f64,
+    typos: usize,
+    seconds: f64,
+}
+
+impl SessionRecord {
+    pub fn new(timestamp: u64, lesson: &str, test_results: &TestResults) -> Self {
+        Self {
+            timestamp,
+            lesson: lesson.to_string(),
+            wpm: test_results.wpm_avg(),
+            accuracy: test_results.accuracy(),
+            typos: test_results.typo_cnt(),
+            seconds: test_results.duration_secs(),
+        }
+    }
+}
+
+/// Add `session_record` to the history in the data directory.
+#[throws]
+pub(crate) fn record(session_record: SessionRecord) {
+    let mut history: Vec<SessionRecord> = records::load(HISTORY_FILE)?;
+    history.push(session_record);
+    records::save(HISTORY_FILE, &history)?;
+}
+
+/// Delete the history in the data directory, if there is one.
+#[throws]
+pub(crate) fn clear() {
+    records::remove(HISTORY_FILE)?;
+}
+
+/// The ISO 8601 date `days` days after the Unix epoch.
+pub(crate) fn date(days: u64) -> String {
+    // Howard Hinnant's civil_from_days, for dates from 1970 onwards.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Quote `field` if it has anything in it that would otherwise break up the
+/// row, like the commas a lesson alphabet can have.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn row(days: u64, lesson: &str, test_results: &TestResults) -> String {
+    format!(
+        "{},{},{:.1},{:.1},{},{:.1}",
+        date(days),
+        escape(lesson),
+        test_results.wpm_avg(),
+        test_results.accuracy(),
+        test_results.typo_cnt(),
+        test_results.duration_secs()
+    )
+}
+
+/// Add a row for a test of `lesson` taken `days` days after the Unix epoch to
+/// the CSV file at `path`, starting it with a header if it's new.
+#[throws(io::Error)]
+pub(crate) fn append(path: &Path, days: u64, lesson: &str, test_results: &TestResults) {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", HEADER)?;
+    }
+    writeln!(file, "{}", row(days, lesson, test_results))?;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(20_740), "2026-10-14");
+    }
+
+    #[test]
+    fn test_session_record() {
+        let test_results = TestResults::from(vec![]);
+
+        assert_eq!(
+            SessionRecord::new(86_400, "aoeu", &test_results),
+            SessionRecord {
+                timestamp: 86_400,
+                lesson: "aoeu".to_string(),
+                wpm: 0.0,
+                accuracy: 100.0,
+                typos: 0,
+                seconds: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("aoeu"), "aoeu");
+        assert_eq!(escape("a,\"b"), "\"a,\"\"b\"");
+    }
+}
@@ -1,5 +1,106 @@
+use std::{
+    fmt,
+    io::{stdout, Write},
+};
+
+use clap::ValueEnum;
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode},
+    execute,
+    style::{style, PrintStyledContent, StyledContent},
+    terminal::{Clear, ClearType},
+    ErrorKind, ExecutableCommand,
+};
+use fehler::throws;
+use serde::{Deserialize, Serialize};
+
+use tui::{
+    backend::Backend,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Text},
+    Terminal,
+};
+
+use crate::{
+    hands::{self, Finger, Hand},
+    heatmap, keymap,
+    theme::Theme,
+    Error, TerminalGuard,
+};
+
+const DIGITS: &str = "1234567890";
+const SYMBOLS: &str = "!@#$%^&*()";
+
+/// What the number row types without shift.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum NumberRow {
+    /// Digits, with symbols on shift.
+    Digits,
+    /// Symbols, with digits on shift, as programmers' variants of the layout
+    /// have it.
+    Symbols,
+}
+
+impl NumberRow {
+    /// Translates `c`, as typed with a digits-first number row, into what the
+    /// same keystroke types with this variant.
+    pub fn remap(self, c: char) -> char {
+        if self == Self::Digits {
+            return c;
+        }
+        if let Some(idx) = DIGITS.chars().position(|d| d == c) {
+            SYMBOLS.chars().nth(idx).unwrap()
+        } else if let Some(idx) = SYMBOLS.chars().position(|s| s == c) {
+            DIGITS.chars().nth(idx).unwrap()
+        } else {
+            c
+        }
+    }
+
+    /// What the number row types with shift.
+    pub fn shifted_row(self) -> String {
+        SYMBOLS.chars().map(|c| self.remap(c)).collect()
+    }
+
+    /// Whether typing `c` takes shift.
+    pub fn is_shifted(self, c: char) -> bool {
+        let c = self.remap(c);
+
+        heatmap::unshifted(c) != c
+    }
+
+    /// The lessons of shifted number row symbols: those of each hand on its
+    /// own, then all of them together.
+    pub fn symbol_lessons(self) -> Vec<String> {
+        let row = self.shifted_row();
+        let (left, right) = row.split_at(5);
+
+        vec![left.to_string(), right.to_string(), row]
+    }
+}
+
+/// Which half of the keyboard is mirrored onto the other for typing one
+/// handed, so that either hand's fingers type the same keys.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum Mirror {
+    Left,
+    Right,
+}
+
+impl Mirror {
+    /// The hand whose keys are kept.
+    pub fn hand(self) -> Hand {
+        match self {
+            Self::Left => Hand::Left,
+            Self::Right => Hand::Right,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
-enum Key {
+pub(crate) enum Key {
     BackTick,
     One,
     Two,
@@ -49,7 +150,7 @@ enum Key {
     Z,
 }
 
-fn key_code_to_key(code: KeyCode) -> Option<Key> {
+pub(crate) fn key_code_to_key(code: KeyCode) -> Option<Key> {
     if let KeyCode::Char(c) = code {
         let key = match c {
             '`' | '~' => Key::BackTick,
@@ -99,7 +200,7 @@ fn key_code_to_key(code: KeyCode) -> Option<Key> {
             'w' | 'W' => Key::W,
             'v' | 'V' => Key::V,
             'z' | 'Z' => Key::Z,
-            _ => unreachable!(),
+            _ => return None,
         };
 
         Some(key)
@@ -110,7 +211,15 @@ fn key_code_to_key(code: KeyCode) -> Option<Key> {
 
 impl fmt::Display for Key {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = match self {
+        write!(f, "{:^4}", self.legend())
+    }
+}
+
+impl Key {
+    /// What's printed on the key: its character, followed by the shifted one
+    /// if that isn't just the capital.
+    fn legend(&self) -> &'static str {
+        match self {
             Self::BackTick => "`~",
             Self::One => "1!",
             Self::Two => "2@",
@@ -134,9 +243,9 @@ impl fmt::Display for Key {
             Self::C => "c",
             Self::R => "r",
             Self::L => "l",
-            Self::ForwardSlash => "\\|",
+            Self::ForwardSlash => "/?",
             Self::Equal => "=+",
-            Self::BackSlash => "/?",
+            Self::BackSlash => "\\|",
             Self::A => "a",
             Self::O => "o",
             Self::E => "e",
@@ -158,19 +267,74 @@ impl fmt::Display for Key {
             Self::W => "w",
             Self::V => "v",
             Self::Z => "z",
-        };
+        }
+    }
+
+    /// The character the key types without shift. Every legend is ASCII, so
+    /// its first byte is that character.
+    pub fn unshifted(&self) -> char {
+        char::from(self.legend().as_bytes()[0])
+    }
+
+    /// The key's label, with the number row's characters the right way round
+    /// for `number_row`.
+    fn label(&self, number_row: NumberRow) -> String {
+        let label = self.to_string();
+        if number_row == NumberRow::Digits {
+            return label;
+        }
+
+        let chars = label.trim().chars().collect::<Vec<char>>();
+        match chars.as_slice() {
+            [digit, symbol] if digit.is_ascii_digit() => {
+                format!("{:^4}", format!("{}{}", symbol, digit))
+            }
+            _ => label,
+        }
+    }
+
+    /// The finger that presses the key when touch typing.
+    pub fn finger(&self) -> Finger {
+        hands::finger(self.unshifted()).expect("every key is typed by a finger")
+    }
+}
 
-        write!(f, "{:^4}", c)
+/// The colour a finger's keys are tinted, the same for both hands and clear
+/// of the red and green used to light keys up.
+fn finger_colour(finger: Finger) -> Color {
+    match finger {
+        Finger::LeftPinky | Finger::RightPinky => Color::Magenta,
+        Finger::LeftRing | Finger::RightRing => Color::Blue,
+        Finger::LeftMiddle | Finger::RightMiddle => Color::Cyan,
+        Finger::LeftIndex | Finger::RightIndex => Color::Yellow,
     }
 }
 
-struct Keyboard {
+/// The crossterm equivalent of `colour`, for printing straight to the
+/// terminal. Only the colours keys are shown in are needed.
+fn crossterm_colour(colour: Color) -> crossterm::style::Color {
+    match colour {
+        Color::Red => crossterm::style::Color::Red,
+        Color::Green => crossterm::style::Color::Green,
+        Color::Magenta => crossterm::style::Color::Magenta,
+        Color::Blue => crossterm::style::Color::Blue,
+        Color::Cyan => crossterm::style::Color::Cyan,
+        Color::Yellow => crossterm::style::Color::Yellow,
+        _ => crossterm::style::Color::Reset,
+    }
+}
+
+pub(crate) struct Keyboard {
     keys: Vec<Key>,
     numberrow_cnt: usize,
     toprow_cnt: usize,
     homerow_cnt: usize,
     _bottomrow_cnt: usize,
     pressed: Option<Key>,
+    /// The key to press next, lit up green to guide the way.
+    next: Option<Key>,
+    number_row: NumberRow,
+    mirror: Option<Mirror>,
 }
 
 impl Default for Keyboard {
@@ -232,11 +396,32 @@ impl Default for Keyboard {
             homerow_cnt: 11,
             _bottomrow_cnt: 10,
             pressed: None,
+            next: None,
+            number_row: NumberRow::Digits,
+            mirror: None,
         }
     }
 }
 
 impl Keyboard {
+    pub fn new(number_row: NumberRow, mirror: Option<Mirror>) -> Self {
+        Self {
+            number_row,
+            mirror,
+            ..Self::default()
+        }
+    }
+
+    /// The key typed from `key`'s position, which is a different one on the
+    /// mirrored half of the keyboard.
+    fn shown(&self, key: Key) -> Key {
+        let half = match self.mirror {
+            Some(half) => half,
+            None => return key,
+        };
+        key_code_to_key(KeyCode::Char(keymap::mirror(key.unshifted(), half))).unwrap_or(key)
+    }
+
     fn numberrow_idx(&self) -> usize {
         0
     }
@@ -253,9 +438,13 @@ impl Keyboard {
         self.homerow_idx() + self.homerow_cnt
     }
 
-    fn key_pressed(&mut self, key: Option<Key>) {
+    pub fn key_pressed(&mut self, key: Option<Key>) {
         self.pressed = key;
     }
+
+    pub fn key_next(&mut self, key: Option<Key>) {
+        self.next = key;
+    }
 }
 
 impl fmt::Display for Keyboard {
@@ -271,17 +460,25 @@ impl fmt::Display for Keyboard {
 }
 
 impl Keyboard {
+    /// The colour `key` is shown in: green if it's to be pressed next, red if
+    /// it was just pressed, and otherwise tinted by finger. Where to go next
+    /// matters more than where the finger just was.
+    fn key_colour(&self, key: Key) -> Color {
+        if Some(key) == self.next {
+            Color::Green
+        } else if Some(key) == self.pressed {
+            Color::Red
+        } else {
+            finger_colour(key.finger())
+        }
+    }
+
     fn to_styled(&self) -> Vec<StyledContent<String>> {
         let mut styled = vec![];
 
-        for key in &self.keys {
-            let mut styled_key = style(key.to_string());
-            if let Some(pressed) = self.pressed {
-                if *key == pressed {
-                    styled_key = styled_key.red();
-                }
-            };
-            styled.push(styled_key);
+        for key in self.keys.iter().map(|key| self.shown(*key)) {
+            let colour = crossterm_colour(self.key_colour(key));
+            styled.push(style(key.label(self.number_row)).with(colour));
         }
 
         // NOTE: These are in reverse order so the indices don't interact in weird ways
@@ -291,13 +488,249 @@ impl Keyboard {
 
         styled
     }
+
+    /// The keyboard laid out as for `to_styled`, but for drawing in the TUI,
+    /// with each key styled by `key_style`.
+    fn to_text(&self, key_style: impl Fn(Key) -> Style) -> Vec<Text<'static>> {
+        let mut text = self
+            .keys
+            .iter()
+            .map(|key| self.shown(*key))
+            .map(|key| Text::styled(key.label(self.number_row), key_style(key)))
+            .collect::<Vec<Text>>();
+
+        // NOTE: These are in reverse order so the indices don't interact in weird ways
+        text.insert(self.bottomrow_idx(), Text::raw("\n          "));
+        text.insert(self.homerow_idx(), Text::raw("\n        "));
+        text.insert(self.toprow_idx(), Text::raw("\n      "));
+
+        text
+    }
+
+    /// The keyboard for drawing in the TUI, coloured like `to_styled`.
+    pub fn styled_text(&self) -> Vec<Text<'static>> {
+        self.to_text(|key| Style::default().fg(self.key_colour(key)))
+    }
+}
+
+/// Where the fingers rest, from the left pinky to the right.
+const HOME_KEYS: [Key; 8] = [
+    Key::A,
+    Key::O,
+    Key::E,
+    Key::U,
+    Key::H,
+    Key::T,
+    Key::N,
+    Key::S,
+];
+
+/// Show the keyboard with the home keys picked out and which finger rests on
+/// each, until a key is pressed.
+#[throws]
+pub(crate) fn show_home_keys<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    number_row: NumberRow,
+    mirror: Option<Mirror>,
+) {
+    let keyboard = Keyboard::new(number_row, mirror);
+    let mut text = keyboard.to_text(|key| {
+        if HOME_KEYS.contains(&key) {
+            theme.correct.modifier(Modifier::BOLD)
+        } else {
+            theme.preview
+        }
+    });
+
+    text.push(Text::raw("\n\n"));
+    for key in &HOME_KEYS {
+        let c = key.unshifted();
+        if let Some(finger) = hands::finger(c) {
+            text.push(Text::styled(c.to_string(), theme.correct));
+            text.push(Text::raw(format!(" {}\n", finger.name())));
+        }
+    }
+
+    terminal.draw(|mut frame| {
+        let block = Block::default()
+            .title("home keys (any key to go back)")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, frame.size());
+    })?;
+
+    while !matches!(event::read()?, Event::Key(_)) {}
 }
 
 #[throws(ErrorKind)]
-fn print_keyboard(keyboard: &Keyboard) {
+pub(crate) fn print_keyboard(keyboard: &Keyboard) {
     execute!(stdout(), Clear(ClearType::All), MoveTo(0, 0),)?;
     for sc in keyboard.to_styled() {
         stdout().execute(PrintStyledContent(sc))?;
     }
 }
 
+/// Show the keyboard, lighting up each key as it's pressed, until Esc.
+#[throws]
+pub(crate) fn explore(number_row: NumberRow, mirror: Option<Mirror>) {
+    let mut keyboard = Keyboard::new(number_row, mirror);
+    let _guard = TerminalGuard::enter()?;
+
+    loop {
+        print_keyboard(&keyboard)?;
+
+        if let Event::Key(event) = event::read()? {
+            if event.code == KeyCode::Esc || keymap::is_interrupt(&event) {
+                break;
+            }
+            let code = match (keymap::qwerty_to_dvorak(event.code), mirror) {
+                (KeyCode::Char(c), Some(half)) => KeyCode::Char(keymap::mirror(c, half)),
+                (code, _) => code,
+            };
+            keyboard.key_pressed(key_code_to_key(code));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_display_round_trip() {
+        for key in Keyboard::default().keys {
+            for c in key.to_string().trim().chars() {
+                assert_eq!(key_code_to_key(KeyCode::Char(c)), Some(key), "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_styled_text_lights_up_pressed_key() {
+        let mut keyboard = Keyboard::default();
+        keyboard.key_pressed(key_code_to_key(KeyCode::Char('e')));
+
+        let lit = keyboard
+            .styled_text()
+            .into_iter()
+            .filter_map(|text| match text {
+                Text::Styled(label, style) if style.fg == Color::Red => Some(label),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lit.len(), 1);
+        assert_eq!(lit[0].trim(), "e");
+    }
+
+    #[test]
+    fn test_backends_agree_on_colours() {
+        let mut keyboard = Keyboard::default();
+        keyboard.key_pressed(Some(Key::E));
+        keyboard.key_next(Some(Key::U));
+
+        let styled = keyboard
+            .to_styled()
+            .into_iter()
+            .map(|styled| styled.style().foreground_color)
+            .collect::<Vec<_>>();
+        let text = keyboard
+            .styled_text()
+            .into_iter()
+            .map(|text| match text {
+                Text::Styled(_, style) => Some(crossterm_colour(style.fg)),
+                Text::Raw(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(styled.len(), text.len());
+        for (styled, text) in styled.into_iter().zip(text) {
+            if let Some(text) = text {
+                assert_eq!(styled, Some(text));
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_key_wins_over_pressed() {
+        let mut keyboard = Keyboard::default();
+        keyboard.key_pressed(Some(Key::E));
+        keyboard.key_next(Some(Key::E));
+        let styled = keyboard.to_styled();
+        let e = styled
+            .iter()
+            .find(|styled| styled.content().trim() == "e")
+            .unwrap();
+        assert_eq!(
+            e.style().foreground_color,
+            Some(crossterm::style::Color::Green)
+        );
+
+        keyboard.key_next(Some(Key::U));
+        let colours = keyboard
+            .styled_text()
+            .into_iter()
+            .filter_map(|text| match text {
+                Text::Styled(label, style) if [Color::Red, Color::Green].contains(&style.fg) => {
+                    Some((label.trim().to_string(), style.fg))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colours,
+            vec![
+                ("e".to_string(), Color::Red),
+                ("u".to_string(), Color::Green)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_finger() {
+        for key in Keyboard::default().keys {
+            assert!(key.finger().keys().contains(key.unshifted()), "{:?}", key);
+        }
+        assert_eq!(Key::A.finger(), Finger::LeftPinky);
+        assert_eq!(Key::H.finger(), Finger::RightIndex);
+
+        let keyboard = Keyboard::default();
+        let a = keyboard
+            .styled_text()
+            .into_iter()
+            .find_map(|text| match text {
+                Text::Styled(label, style) if label.trim() == "a" => Some(style.fg),
+                _ => None,
+            });
+        assert_eq!(a, Some(Color::Magenta));
+    }
+
+    #[test]
+    fn test_number_row_symbols() {
+        assert_eq!(NumberRow::Symbols.remap('1'), '!');
+        assert_eq!(NumberRow::Symbols.remap('('), '9');
+        assert_eq!(NumberRow::Symbols.remap('a'), 'a');
+        assert_eq!(NumberRow::Digits.remap('1'), '1');
+
+        assert_eq!(Key::Seven.label(NumberRow::Symbols).trim(), "&7");
+        assert_eq!(Key::Seven.label(NumberRow::Digits).trim(), "7&");
+        assert_eq!(Key::ForwardSlash.label(NumberRow::Symbols).trim(), "/?");
+    }
+
+    #[test]
+    fn test_shifted_row() {
+        assert_eq!(NumberRow::Digits.shifted_row(), "!@#$%^&*()");
+        assert_eq!(NumberRow::Symbols.shifted_row(), "1234567890");
+
+        assert!(NumberRow::Digits.is_shifted('!'));
+        assert!(NumberRow::Digits.is_shifted('A'));
+        assert!(!NumberRow::Digits.is_shifted('1'));
+        assert!(NumberRow::Symbols.is_shifted('1'));
+        assert!(!NumberRow::Symbols.is_shifted('!'));
+
+        assert_eq!(
+            NumberRow::Digits.symbol_lessons(),
+            vec!["!@#$%", "^&*()", "!@#$%^&*()"]
+        );
+    }
+}
@@ -1,4 +1,19 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    hands,
+    heatmap::{self, ROWS},
+    keyboard::Mirror,
+};
+
+/// Where in each of the rows the keys typed by the right hand start.
+const SPLITS: [usize; 4] = [6, 5, 5, 5];
+
+/// Whether `event` is Ctrl+C, which raw mode delivers as an ordinary key press
+/// rather than a signal.
+pub(crate) fn is_interrupt(event: &KeyEvent) -> bool {
+    event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL)
+}
 
 pub(crate) fn qwerty_to_dvorak(code: KeyCode) -> KeyCode {
     if let KeyCode::Char(c) = code {
@@ -77,3 +92,66 @@ pub(crate) fn qwerty_to_dvorak(code: KeyCode) -> KeyCode {
         code
     }
 }
+
+/// What `c` types with `half` of the keyboard mirrored onto the other one:
+/// itself if it's on that half, otherwise the key in the same place on the
+/// mirrored half. The keys on the far edge have nothing to mirror, so stay as
+/// they are.
+pub(crate) fn mirror(c: char, half: Mirror) -> char {
+    let key = heatmap::unshifted(c);
+    if hands::hand(key) == Some(half.hand()) {
+        return c;
+    }
+
+    for (row, split) in ROWS.iter().zip(SPLITS) {
+        let keys = row.chars().collect::<Vec<char>>();
+        if let Some(idx) = keys.iter().position(|k| *k == key) {
+            return match (2 * split)
+                .checked_sub(idx + 1)
+                .map(|partner| keys[partner])
+            {
+                Some(partner) if key != c => heatmap::shifted(partner),
+                Some(partner) => partner,
+                None => c,
+            };
+        }
+    }
+
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_interrupt() {
+        assert!(is_interrupt(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_interrupt(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::NONE
+        )));
+    }
+
+    #[test]
+    fn test_apostrophe() {
+        assert_eq!(qwerty_to_dvorak(KeyCode::Char('q')), KeyCode::Char('\''));
+        assert_eq!(qwerty_to_dvorak(KeyCode::Char('Q')), KeyCode::Char('"'));
+        assert_eq!(qwerty_to_dvorak(KeyCode::Char('\'')), KeyCode::Char('-'));
+    }
+
+    #[test]
+    fn test_mirror() {
+        assert_eq!(mirror('s', Mirror::Left), 'a');
+        assert_eq!(mirror('H', Mirror::Left), 'U');
+        assert_eq!(mirror('6', Mirror::Left), '5');
+        assert_eq!(mirror('{', Mirror::Left), '~');
+        assert_eq!(mirror('a', Mirror::Left), 'a');
+        assert_eq!(mirror('-', Mirror::Left), '-');
+        assert_eq!(mirror('\'', Mirror::Right), 'l');
+        assert_eq!(mirror(' ', Mirror::Right), ' ');
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::HashSet, fmt, fs, ops::RangeInclusive, path::Path};
+
+use fehler::throws;
+
+use crate::{heatmap, Error};
+
+/// The built-in lessons, as (name, alphabet) pairs.
+pub(crate) const LESSONS: &[(&str, &str)] = &[
+    ("Home row, 8 keys (starting positions)", "aoeuhtns"),
+    ("Home row, 10 keys", "aoeuidhtns"),
+    ("Home row + C, F, K, L, M, P, R, V", "aoeuidhtnscfklmprv"),
+    ("Home row + B, G, J, Q, W, X, Y, Z", "aoeuidhtnsbgjqwxyz"),
+    ("The entire roman alphabet", "abcdefghijklmnopqrstuvwxyz"),
+];
+
+/// The name of the built-in lesson with `alphabet`, if there is one.
+pub(crate) fn name(alphabet: &str) -> Option<&'static str> {
+    LESSONS
+        .iter()
+        .find(|(_, lesson)| *lesson == alphabet)
+        .map(|(name, _)| *name)
+}
+
+/// Load lesson alphabets from a file, one per line. Blank lines and lines
+/// starting with `#` are skipped.
+#[throws]
+pub(crate) fn load(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Problem {
+    UnsupportedChars(String),
+    NoMatchingWords,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedChars(chars) => write!(f, "unsupported characters {:?}", chars),
+            Self::NoMatchingWords => write!(
+                f,
+                "no words of a permitted length can be typed with this alphabet"
+            ),
+        }
+    }
+}
+
+/// Check that `alphabet` can be typed on the keyboard and that at least one
+/// word in `word_list` with a length in `lengths` can be made from it, so a
+/// lesson won't search forever for words.
+pub(crate) fn validate(
+    alphabet: &str,
+    word_list: &[&str],
+    lengths: &RangeInclusive<usize>,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    let unsupported = alphabet
+        .chars()
+        .filter(|c| !c.is_ascii_graphic())
+        .collect::<String>();
+    if !unsupported.is_empty() {
+        problems.push(Problem::UnsupportedChars(unsupported));
+    }
+
+    if candidate_cnt(alphabet, word_list, lengths) == 0 {
+        problems.push(Problem::NoMatchingWords);
+    }
+
+    problems
+}
+
+/// How hard `word` is to type, for ordering a lesson from easy to hard: a
+/// point for each character, another for each one off the home row, and two
+/// more for each of the lesson's `new_keys`, which haven't been practised yet.
+pub(crate) fn difficulty(word: &str, new_keys: &HashSet<char>) -> usize {
+    let home_row = heatmap::ROWS[2];
+
+    word.chars()
+        .map(|c| {
+            let key = heatmap::unshifted(c);
+            1 + usize::from(!home_row.contains(key)) + 2 * usize::from(new_keys.contains(&c))
+        })
+        .sum()
+}
+
+/// How many different words in `word_list` with a length in `lengths` can be
+/// made from `alphabet`.
+pub(crate) fn candidate_cnt(
+    alphabet: &str,
+    word_list: &[&str],
+    lengths: &RangeInclusive<usize>,
+) -> usize {
+    let allowed = alphabet.chars().collect::<HashSet<char>>();
+
+    word_list
+        .iter()
+        .filter(|word| {
+            lengths.contains(&word.chars().count()) && word.chars().all(|c| allowed.contains(&c))
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ok() {
+        assert_eq!(
+            validate("aoeuhtns", &["zebra", "those"], &(0..=usize::MAX)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(name("aoeuidhtns"), Some("Home row, 10 keys"));
+        assert_eq!(name("abc"), None);
+    }
+
+    #[test]
+    fn test_candidate_cnt() {
+        let word_list = ["those", "hut", "those", "zebra", "at"];
+
+        assert_eq!(candidate_cnt("aoeuhtns", &word_list, &(0..=usize::MAX)), 3);
+        assert_eq!(candidate_cnt("aoeuhtns", &word_list, &(3..=usize::MAX)), 2);
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let new_keys = ['c'].iter().copied().collect::<HashSet<char>>();
+
+        assert_eq!(difficulty("those", &new_keys), 5);
+        assert_eq!(difficulty("Tap", &new_keys), 4);
+        assert_eq!(difficulty("cat", &new_keys), 6);
+    }
+
+    #[test]
+    fn test_candidate_cnt_apostrophe() {
+        let word_list = ["don't", "those"];
+
+        assert_eq!(
+            candidate_cnt("aoeuidhtns", &word_list, &(0..=usize::MAX)),
+            1
+        );
+        assert_eq!(
+            candidate_cnt("aoeuidhtns'", &word_list, &(0..=usize::MAX)),
+            2
+        );
+    }
+
+    #[test]
+    fn test_validate_problems() {
+        assert_eq!(
+            validate("aé", &["zebra", "those"], &(0..=usize::MAX)),
+            vec![
+                Problem::UnsupportedChars("é".to_string()),
+                Problem::NoMatchingWords
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_lengths() {
+        assert_eq!(
+            validate("aoeuhtns", &["at", "those"], &(3..=4)),
+            vec![Problem::NoMatchingWords]
+        );
+        assert_eq!(validate("aoeuhtns", &["at", "those"], &(2..=4)), vec![]);
+    }
+}
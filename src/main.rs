@@ -1,49 +1,148 @@
+mod compose;
+mod config;
+mod hands;
+mod heatmap;
+mod history;
+mod keyboard;
 mod keymap;
+mod lessons;
+mod menu;
 mod metrics;
+mod quotes;
+mod records;
+mod results;
+mod scheduler;
+mod sentence;
+mod theme;
 mod typingtest;
+mod widgets;
 mod word;
+mod wordlist;
 
 use std::{
     collections::{HashSet, VecDeque},
     fmt::{self, Display, Formatter},
+    fs::{self, OpenOptions},
     io::{stdout, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+use clap::{Parser, ValueEnum};
 use crossterm::{
-    event::{read, Event, KeyCode},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use fehler::throws;
-use rand::seq::SliceRandom;
-use resource::resource_str;
+use fehler::{throw, throws};
+use rand::{seq::SliceRandom, Rng};
 use tui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph, Sparkline, Text},
+    backend::{Backend, CrosstermBackend},
     Terminal,
 };
 
-use typingtest::typing_test;
+use config::Config;
+use history::SessionRecord;
+use lessons::LESSONS;
+use records::{Records, ResumePoint, WeakWords};
+use results::{ask_again, show_results, show_session_summary, PersonalBest, Summary};
+use scheduler::Schedule;
+use theme::Theme;
+use typingtest::{endless_typing_test, race_typing_test, show_splash, typing_test, TestResults};
+use word::FinishedWord;
+use wordlist::Difficulty;
 
+/// How many words a `--drill-weak` or `--finger` test has, repeating the weak
+/// words as needed.
+const DRILL_WORDS: usize = 50;
+
+/// How many of each test's worst words are kept for `--drill-weak`.
+const WEAK_WORDS_PER_TEST: usize = 10;
+
+/// With `--contractions`, every this many words of a lesson is a contraction.
+const CONTRACTION_EVERY: usize = 5;
+
+/// How many words a `--review` test adds that haven't been reviewed before.
+const REVIEW_NEW_WORDS: usize = 10;
+
+/// How many typeable words without any of a lesson's new keys are passed over
+/// before one is taken anyway.
+const FAVOUR_TRIES: usize = 3;
+
+/// How many different real words a `--finger` drill needs before it uses them
+/// rather than made-up sequences of the finger's keys.
+const FINGER_MIN_WORDS: usize = 10;
+
+/// Add the worst words of a test to those to drill next time.
+fn collect_weak_words(weak_words: &mut Vec<String>, test_results: &TestResults) {
+    if test_results.pasted() {
+        return;
+    }
+    for word in test_results.worst_words(WEAK_WORDS_PER_TEST) {
+        if !weak_words.iter().any(|weak| weak == word) {
+            weak_words.push(word.to_string());
+        }
+    }
+}
+
+/// Split `text` up into words to be typed in order, keeping the line breaks by
+/// leaving a newline on the end of the last word of each line.
+fn line_words(text: &str, lengths: &RangeInclusive<usize>) -> Vec<String> {
+    let mut words = vec![];
+    for line in text.lines() {
+        let line_words = line
+            .split_whitespace()
+            .filter(|word| lengths.contains(&word.chars().count()))
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+        if let Some((last, rest)) = line_words.split_last() {
+            words.extend_from_slice(rest);
+            words.push(format!("{}\n", last));
+        }
+    }
+
+    words
+}
+
+/// Pick `amount` words typed with only the `allowed` characters, preferring
+/// ones with at least one `favoured` character. A word without any is only
+/// taken after `FAVOUR_TRIES` typeable ones in a row didn't have any either, so
+/// a lesson whose new keys are rare still finds its words. Typeable words are
+/// also weighed up as common or rare for the `difficulty`. If `unique` is set
+/// no word is picked twice, so there must be at least `amount` to pick from.
 fn get_test_words<'a>(
     word_list: &[&'a str],
     allowed: &HashSet<char>,
+    favoured: &HashSet<char>,
+    lengths: &RangeInclusive<usize>,
+    difficulty: Difficulty,
     amount: usize,
+    unique: bool,
 ) -> VecDeque<&'a str> {
     let mut rng = rand::thread_rng();
     let mut words = VecDeque::new();
+    let mut picked = HashSet::new();
+    let common = wordlist::common();
 
     let mut word;
     let mut chars;
     for _ in 0..amount {
+        let mut tries = 0;
         'search: loop {
             word = word_list.choose(&mut rng).unwrap();
             chars = word.chars().collect::<HashSet<char>>();
-            if chars.is_subset(allowed) {
-                words.push_back(*word);
-                break 'search;
+            if chars.is_subset(allowed)
+                && lengths.contains(&word.chars().count())
+                && !(unique && picked.contains(word))
+                && rng.gen_bool(difficulty.keep_chance(common.contains(word)))
+            {
+                tries += 1;
+                if favoured.is_empty() || !chars.is_disjoint(favoured) || tries > FAVOUR_TRIES {
+                    words.push_back(*word);
+                    picked.insert(*word);
+                    break 'search;
+                }
             }
         }
     }
@@ -51,147 +150,849 @@ fn get_test_words<'a>(
     words
 }
 
+/// How to pick the words for a lesson, worked out once so that an endless
+/// test can keep picking more of them.
+struct LessonWords<'a, 'w> {
+    config: &'a Config,
+    word_list: &'a [&'w str],
+    lengths: &'a RangeInclusive<usize>,
+    allowed: HashSet<char>,
+    /// The keys this lesson introduces, which the previous lesson's words
+    /// couldn't practise, and so are leant on.
+    new_keys: HashSet<char>,
+    contractions: Vec<&'static str>,
+    /// How many different words can be typed in the lesson.
+    candidates: usize,
+    /// Words are picked with replacement unless asked otherwise, and then
+    /// only if there are enough to go round, so a lesson never runs out.
+    unique: bool,
+}
+
+impl<'a, 'w> LessonWords<'a, 'w> {
+    fn new(
+        config: &'a Config,
+        lessons: &[String],
+        lesson: usize,
+        word_list: &'a [&'w str],
+        lengths: &'a RangeInclusive<usize>,
+    ) -> Self {
+        let mut allowed = lessons[lesson].chars().collect::<HashSet<char>>();
+        let mut previous = match lesson.checked_sub(1) {
+            Some(previous) => lessons[previous].chars().collect(),
+            None => HashSet::new(),
+        };
+        if config.contractions {
+            allowed.insert('\'');
+            previous.insert('\'');
+        }
+        let new_keys = allowed.difference(&previous).copied().collect();
+
+        let candidates = lessons::candidate_cnt(&lessons[lesson], word_list, lengths);
+        // There are far too few contractions for them to come up by chance.
+        let contractions = wordlist::CONTRACTIONS
+            .iter()
+            .copied()
+            .filter(|word| {
+                word.chars().all(|c| allowed.contains(&c))
+                    && lengths.contains(&word.chars().count())
+            })
+            .collect();
+
+        Self {
+            config,
+            word_list,
+            lengths,
+            allowed,
+            new_keys,
+            contractions,
+            candidates,
+            unique: config.no_repeats && candidates >= config.words,
+        }
+    }
+
+    fn pick(&self) -> VecDeque<&'w str> {
+        let mut test_words = get_test_words(
+            self.word_list,
+            &self.allowed,
+            &self.new_keys,
+            self.lengths,
+            self.config.difficulty,
+            self.config.words,
+            self.unique,
+        );
+        if self.config.contractions && !self.contractions.is_empty() {
+            let mut rng = rand::thread_rng();
+            for word in test_words.iter_mut().step_by(CONTRACTION_EVERY) {
+                *word = self.contractions.choose(&mut rng).unwrap();
+            }
+        }
+        if self.config.ramp {
+            test_words
+                .make_contiguous()
+                .sort_by_key(|word| lessons::difficulty(word, &self.new_keys));
+        }
+
+        test_words
+    }
+}
+
 #[allow(clippy::enum_variant_names)]
 #[derive(thiserror::Error, Debug)]
 enum Error {
+    /// Bad command line arguments, or files they point to that can't be used.
+    ConfigError(String),
     CrosstermError(#[from] crossterm::ErrorKind),
     IoError(#[from] std::io::Error),
+    JsonError(#[from] serde_json::Error),
     SystemTimeError(#[from] std::time::SystemTimeError),
+    /// Something saved in the data directory that can't be read, and so is
+    /// left as it is.
+    DataError {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A saved session written in a format this version can't read.
+    VersionError {
+        found: u32,
+        supported: u32,
+    },
 }
 
 impl Display for Error {
     #[throws(fmt::Error)]
     fn fmt(&self, f: &mut Formatter<'_>) {
-        write!(f, "{:?}", self)?;
+        match self {
+            Self::ConfigError(message) => write!(f, "{}", message)?,
+            Self::DataError { path, source } => write!(
+                f,
+                "{} can't be read ({}), so it's been left alone; fix or delete it, or run with --reset",
+                path.display(),
+                source
+            )?,
+            Self::VersionError { found, supported } => write!(
+                f,
+                "the session was saved in format version {}, but only versions up to {} can be read",
+                found, supported
+            )?,
+            _ => write!(f, "{:?}", self)?,
+        }
+    }
+}
+
+/// Append `err` and everything that led to it to the log at `path`, so there's
+/// something to go on in a bug report.
+#[throws(std::io::Error)]
+fn log_error(path: &Path, err: &Error) {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let mut log = OpenOptions::new().create(true).append(true).open(path)?;
+
+    writeln!(
+        log,
+        "{}T{:02}:{:02}:{:02}Z error: {}",
+        history::date(secs / 86_400),
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60,
+        err
+    )?;
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        writeln!(log, "  caused by: {}", err)?;
+        source = err.source();
+    }
+}
+
+/// The terminal taken over for the TUI, which is given back when this is
+/// dropped, so that an error partway through a session doesn't leave it raw
+/// and in the alternate screen.
+pub(crate) struct TerminalGuard;
+
+impl TerminalGuard {
+    #[throws]
+    pub fn enter() -> Self {
+        execute!(stdout(), EnterAlternateScreen)?;
+        enable_raw_mode()?;
+
+        Self
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // There's nothing more to be done if the terminal can't be restored.
+        let _ = disable_raw_mode();
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+    }
+}
+
+/// Give the terminal back, then print the `compact_lines` of the tests taken
+/// with `--print-results`. Anything printed before would be lost with the
+/// alternate screen.
+fn leave_terminal(guard: TerminalGuard, config: &Config, compact_lines: &[String]) {
+    drop(guard);
+
+    if config.print_results {
+        for line in compact_lines {
+            println!("{}", line);
+        }
+    }
+}
+
+/// What's kept from one test to the next, whichever mode they're taken in.
+struct Run<'c> {
+    config: &'c Config,
+    theme: &'c Theme,
+    records: Records,
+    /// Everything exported so far, which the export is rewritten with after
+    /// every test so quitting early loses nothing.
+    exported: Vec<serde_json::Value>,
+    compact_lines: Vec<String>,
+}
+
+impl Run<'_> {
+    /// Take a test with `test`, then keep its personal best and history under
+    /// `key`, export it and show the results, returning them along with
+    /// whether to carry on. `key` is the lesson's alphabet, or the flag the
+    /// test was taken with for the other modes.
+    #[throws]
+    fn run_mode<'a, B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        key: &str,
+        test: impl FnOnce(&mut Terminal<B>) -> Result<TestResults<'a>, Error>,
+    ) -> (TestResults<'a>, bool) {
+        let config = self.config;
+        let test_results = test(terminal)?;
+
+        // Read the best before it's (potentially) replaced below.
+        let best = match self.records.best(key) {
+            Some(best) => PersonalBest::Previous(best),
+            None => PersonalBest::FirstAttempt,
+        };
+
+        let new_best = test_results.word_cnt() > 0
+            && !test_results.pasted()
+            && self.records.update(key, test_results.wpm_avg());
+        let newly_mastered = config
+            .target_wpm
+            .filter(|_| config.mastery)
+            .is_some_and(|target| test_results.mastered(target as f64))
+            && self.records.master(key);
+        if new_best || newly_mastered {
+            self.records.save()?;
+        }
+
+        if !test_results.pasted() && test_results.word_cnt() > 0 {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            history::record(SessionRecord::new(timestamp, key, &test_results))?;
+
+            if let Some(path) = &config.history {
+                history::append(path, scheduler::today()?, key, &test_results)?;
+            }
+        }
+
+        if let Some(path) = &config.export {
+            self.exported.push(serde_json::to_value(&test_results)?);
+            fs::write(path, serde_json::to_string_pretty(&self.exported)?)?;
+        }
+
+        let carry_on = show_results(terminal, self.theme, config, &test_results, best)?;
+        self.compact_lines
+            .push(results::compact_line(&test_results));
+
+        (test_results, carry_on)
     }
 }
 
 #[throws]
 fn main() {
-    execute!(stdout(), EnterAlternateScreen)?;
-    enable_raw_mode()?;
+    let config = Config::parse();
+    let log_errors = config.log_errors.clone();
+
+    if let Err(err) = run(config) {
+        if let Some(path) = log_errors {
+            if let Err(log_err) = log_error(&path, &err) {
+                eprintln!("couldn't log the error to {}: {}", path.display(), log_err);
+            }
+        }
+        throw!(err);
+    }
+}
+
+#[throws]
+fn run(config: Config) {
+    if config.reset {
+        records::reset()?;
+        println!("Cleared saved records");
+        return;
+    }
+
+    if let Some(path) = &config.analyze {
+        let contents = fs::read_to_string(path)?;
+        let session = TestResults::parse_session(&contents)?;
+        let summaries = session.iter().map(Summary::from).collect::<Vec<Summary>>();
+
+        if config.json {
+            println!("{}", serde_json::to_string_pretty(&summaries)?);
+        } else {
+            for (idx, summary) in summaries.iter().enumerate() {
+                println!("lesson {}\n{}\n", idx + 1, summary);
+            }
+        }
+        return;
+    }
+
+    if config.show_keyboard {
+        keyboard::explore(config.number_row, config.mirror)?;
+        return;
+    }
+
+    let theme = Theme::from(config.theme);
+    let records = Records::load()?;
+
+    let mut lessons = match &config.lessons {
+        Some(path) => lessons::load(path)?,
+        None => LESSONS
+            .iter()
+            .map(|(_, alphabet)| alphabet.to_string())
+            .collect(),
+    };
+    // Typing one handed only the mirrored half's keys can be reached, which
+    // can leave some lessons the same as the one before.
+    if let Some(half) = config.mirror {
+        let mut reachable: Vec<String> = vec![];
+        for alphabet in lessons {
+            let kept = alphabet
+                .chars()
+                .filter(|c| hands::hand(*c) == Some(half.hand()))
+                .collect::<String>();
+            if !kept.is_empty() && reachable.last() != Some(&kept) {
+                reachable.push(kept);
+            }
+        }
+        lessons = reachable;
+    }
+
+    // A saved lesson that no longer exists just means starting from the top.
+    let first_lesson = if config.resume {
+        ResumePoint::load()?
+            .lesson
+            .filter(|lesson| *lesson < lessons.len())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let weak_words = if config.drill_weak {
+        let weak_words = WeakWords::load()?.words;
+        if weak_words.is_empty() {
+            println!("No weak words saved yet, so running the lessons instead");
+        }
+        weak_words
+    } else {
+        vec![]
+    };
+
+    let builtin_words = wordlist::builtin(config.minimal_words);
+    let custom_words = match &config.word_list {
+        Some(path) => Some(fs::read_to_string(path)?),
+        None => None,
+    };
+    let words: &str = custom_words.as_deref().unwrap_or(&builtin_words);
+    let word_list = words.split_whitespace().collect::<Vec<&str>>();
+    let lengths = config.word_lengths();
+
+    if let Some(path) = &config.validate_lessons {
+        let mut valid = true;
+        for (idx, alphabet) in lessons::load(path)?.iter().enumerate() {
+            let problems = lessons::validate(alphabet, &word_list, &lengths);
+            if problems.is_empty() {
+                println!("lesson {} ({}): OK", idx + 1, alphabet);
+            }
+            for problem in problems {
+                valid = false;
+                println!("lesson {} ({}): ERROR: {}", idx + 1, alphabet, problem);
+            }
+        }
+        process::exit(if valid { 0 } else { 1 });
+    }
+
+    // Catch anything that would leave a test searching forever for words
+    // before the terminal is taken over.
+    if config.words == 0 {
+        throw!(Error::ConfigError("--words must be at least 1".to_string()));
+    }
+    if config.time == Some(0) {
+        throw!(Error::ConfigError(
+            "--time must be at least 1 second".to_string()
+        ));
+    }
+    if lengths.is_empty() {
+        throw!(Error::ConfigError(
+            "--min-len is greater than --max-len".to_string()
+        ));
+    }
+    if word_list.is_empty() {
+        throw!(Error::ConfigError(
+            "the word list contains no words".to_string()
+        ));
+    }
+    let benchmark = match config.benchmark {
+        Some(n) => match n
+            .checked_sub(1)
+            .and_then(|idx| quotes::benchmarks().get(idx).copied())
+        {
+            Some(benchmark) => Some((n, benchmark)),
+            None => throw!(Error::ConfigError(format!(
+                "there is no benchmark {}, only 1 to {}",
+                n,
+                quotes::benchmarks().len()
+            ))),
+        },
+        None => None,
+    };
+    if lessons.is_empty() {
+        throw!(Error::ConfigError(
+            "there are no lessons to run".to_string()
+        ));
+    }
+    if config.ordered {
+        if !word_list
+            .iter()
+            .any(|word| lengths.contains(&word.chars().count()))
+        {
+            throw!(Error::ConfigError(
+                "no words in the word list are of a permitted length".to_string()
+            ));
+        }
+    } else {
+        for (idx, alphabet) in lessons.iter().enumerate() {
+            if let Some(problem) = lessons::validate(alphabet, &word_list, &lengths).first() {
+                throw!(Error::ConfigError(format!(
+                    "lesson {} ({}): {}",
+                    idx + 1,
+                    alphabet,
+                    problem
+                )));
+            }
+        }
+    }
+
+    let race_json = match &config.race {
+        Some(path) => Some(fs::read_to_string(path)?),
+        None => None,
+    };
+    let race_session = race_json
+        .as_deref()
+        .map(TestResults::parse_session)
+        .transpose()?;
+
+    if config.print_words {
+        for lesson in first_lesson..lessons.len() {
+            let mut test_words =
+                LessonWords::new(&config, &lessons, lesson, &word_list, &lengths).pick();
+            let sentences;
+            if config.sentences {
+                sentences =
+                    sentence::to_sentences(test_words.make_contiguous(), &mut rand::thread_rng());
+                test_words = sentences.iter().map(String::as_str).collect();
+            }
+            let test_words = test_words.into_iter().collect::<Vec<&str>>();
+            println!(
+                "lesson {} ({}): {}",
+                lesson + 1,
+                lessons[lesson],
+                test_words.join(" ")
+            );
+        }
+        return;
+    }
+
+    let guard = TerminalGuard::enter()?;
 
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.autoresize()?;
     terminal.hide_cursor()?;
 
-    let words = resource_str!("assets/words_alpha.txt");
-    let word_list = words.split_whitespace().collect::<Vec<&str>>();
+    let mut run = Run {
+        config: &config,
+        theme: &theme,
+        records,
+        exported: vec![],
+        compact_lines: vec![],
+    };
+
+    if config.ordered {
+        let lines;
+        let test_words = if config.multiline {
+            lines = line_words(words, &lengths);
+            lines.iter().map(String::as_str).collect()
+        } else {
+            word_list
+                .iter()
+                .copied()
+                .filter(|word| lengths.contains(&word.chars().count()))
+                .collect::<VecDeque<&str>>()
+        };
+
+        let mode = config.test_mode(test_words.len());
+        run.run_mode(&mut terminal, "--ordered", |terminal| {
+            typing_test(terminal, &config, &theme, None, test_words, mode, None)
+        })?;
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    if !weak_words.is_empty() {
+        // Go round the words in a different order each time until there's
+        // enough of them.
+        let mut rng = rand::thread_rng();
+        let mut test_words = VecDeque::new();
+        while test_words.len() < DRILL_WORDS {
+            let mut round = weak_words.iter().map(String::as_str).collect::<Vec<&str>>();
+            round.shuffle(&mut rng);
+            test_words.extend(round);
+        }
+
+        let mode = config.test_mode(test_words.len());
+        run.run_mode(&mut terminal, "--drill-weak", |terminal| {
+            let test_results =
+                typing_test(terminal, &config, &theme, None, test_words, mode, None)?;
+            let mut drilled = vec![];
+            collect_weak_words(&mut drilled, &test_results);
+            if !drilled.is_empty() {
+                WeakWords { words: drilled }.save()?;
+            }
+            Ok(test_results)
+        })?;
 
-    // Lesson 1 - Home row, 8 keys (starting positions)
-    // Lesson 2 - Home row, 10 keys
-    // Lesson 3 - Home row + C, F, K, L, M, P, R, V
-    // Lesson 4 - Home row + B, G, J, Q, W, X, Y, Z
-    // Lesson 5 - The entire roman alphabet
-    'lessons: for lesson_alphabet in &[
-        "aoeuhtns",
-        "aoeuidhtns",
-        "aoeuidhtnscfklmprv",
-        "aoeuidhtnsbgjqwxyz",
-        "abcdefghijklmnopqrstuvwxyz",
-    ] {
-        let allowed = lesson_alphabet.chars().collect::<HashSet<char>>();
-
-        let test_words = get_test_words(&word_list, &allowed, 100);
-        let test_results = typing_test(&mut terminal, test_words)?;
-
-        terminal.draw(|mut frame| {
-            let rows = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints(
-                    [
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(40),
-                    ]
-                    .as_ref(),
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    if let Some(finger) = config.finger {
+        let allowed = finger.keys().chars().collect::<HashSet<char>>();
+        let finger_words = word_list
+            .iter()
+            .filter(|word| {
+                word.chars().all(|c| allowed.contains(&c))
+                    && lengths.contains(&word.chars().count())
+            })
+            .collect::<HashSet<_>>();
+
+        let sequences;
+        let test_words = if finger_words.len() >= FINGER_MIN_WORDS {
+            get_test_words(
+                &word_list,
+                &allowed,
+                &HashSet::new(),
+                &lengths,
+                config.difficulty,
+                DRILL_WORDS,
+                config.no_repeats && finger_words.len() >= DRILL_WORDS,
+            )
+        } else {
+            sequences =
+                hands::finger_sequences(finger, &lengths, DRILL_WORDS, &mut rand::thread_rng());
+            sequences.iter().map(String::as_str).collect()
+        };
+
+        let key = match finger.to_possible_value() {
+            Some(value) => format!("--finger {}", value.get_name()),
+            None => "--finger".to_string(),
+        };
+        let mode = config.test_mode(test_words.len());
+        run.run_mode(&mut terminal, &key, |terminal| {
+            typing_test(terminal, &config, &theme, None, test_words, mode, None)
+        })?;
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    if config.symbols {
+        let mut rng = rand::thread_rng();
+        for lesson in config.number_row.symbol_lessons() {
+            let sequences = hands::sequences(&lesson, &lengths, DRILL_WORDS, &mut rng);
+            let test_words = sequences.iter().map(String::as_str).collect();
+
+            let mode = config.test_mode(sequences.len());
+            let key = format!("--symbols {}", lesson);
+            let (_, carry_on) = run.run_mode(&mut terminal, &key, |terminal| {
+                typing_test(terminal, &config, &theme, None, test_words, mode, None)
+            })?;
+            if !carry_on {
+                break;
+            }
+        }
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    if config.review {
+        let today = scheduler::today()?;
+        let mut schedule = Schedule::load()?;
+
+        let due = schedule
+            .due(today)
+            .into_iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+        let unseen = word_list
+            .iter()
+            .copied()
+            .filter(|word| lengths.contains(&word.chars().count()) && !schedule.contains(word))
+            .collect::<HashSet<&str>>()
+            .into_iter()
+            .collect::<Vec<&str>>();
+
+        let mut rng = rand::thread_rng();
+        let mut review_words = due.iter().map(String::as_str).collect::<Vec<&str>>();
+        review_words.extend(unseen.choose_multiple(&mut rng, REVIEW_NEW_WORDS));
+        review_words.shuffle(&mut rng);
+
+        if !review_words.is_empty() {
+            let mode = config.test_mode(review_words.len());
+            run.run_mode(&mut terminal, "--review", |terminal| {
+                let test_results = typing_test(
+                    terminal,
+                    &config,
+                    &theme,
+                    None,
+                    review_words.into(),
+                    mode,
+                    None,
+                )?;
+                if !test_results.pasted() {
+                    for word in test_results.words() {
+                        schedule.review(word.value(), !word.has_typo(), today);
+                    }
+                    schedule.save()?;
+                }
+                Ok(test_results)
+            })?;
+        }
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    if let Some((n, benchmark)) = benchmark {
+        let test_words: VecDeque<&str> = benchmark.text.split_whitespace().collect();
+        let mode = config.test_mode(test_words.len());
+        let (test_results, _) =
+            run.run_mode(&mut terminal, &format!("--benchmark {}", n), |terminal| {
+                typing_test(
+                    terminal,
+                    &config,
+                    &theme,
+                    Some(benchmark.author),
+                    test_words,
+                    mode,
+                    None,
                 )
-                .split(frame.size());
-
-            let row_0_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-                .split(rows[0]);
-
-            let row_1_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(
-                    [
-                        Constraint::Percentage(33),
-                        Constraint::Percentage(33),
-                        Constraint::Percentage(33),
-                    ]
-                    .as_ref(),
+            })?;
+
+        leave_terminal(guard, &config, &run.compact_lines);
+
+        // Printed once the terminal's back to normal, so it can be shared.
+        if test_results.word_cnt() > 0 && !test_results.pasted() {
+            println!(
+                "benchmark {} ({}): {:.0} wpm, {:.1}% accuracy",
+                n,
+                benchmark.author,
+                test_results.wpm_avg(),
+                test_results.accuracy()
+            );
+        }
+        return;
+    }
+
+    if config.quotes {
+        loop {
+            let quote = quotes::random(&mut rand::thread_rng());
+            let caption = format!("― {}", quote.author);
+            let test_words: VecDeque<&str> = quote.text.split_whitespace().collect();
+
+            let mode = config.test_mode(test_words.len());
+            let (_, carry_on) = run.run_mode(&mut terminal, "--quotes", |terminal| {
+                typing_test(
+                    terminal,
+                    &config,
+                    &theme,
+                    Some(&caption),
+                    test_words,
+                    mode,
+                    None,
                 )
-                .split(rows[1]);
-
-            let block = Block::default()
-                .title("wpm")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, row_0_chunks[0]);
-            let text = [Text::raw(format!("{:.0}", test_results.wpm_avg()))];
-            let paragraph = Paragraph::new(text.iter()).block(block);
-            frame.render_widget(paragraph, row_0_chunks[0]);
-
-            let block = Block::default()
-                .title("typos")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, row_0_chunks[1]);
-            let text = [Text::raw(format!("{}", test_results.typo_cnt()))];
-            let paragraph = Paragraph::new(text.iter()).block(block);
-            frame.render_widget(paragraph, row_0_chunks[1]);
-
-            let block = Block::default()
-                .title("words typed")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, row_1_chunks[0]);
-            let text = [Text::raw(format!("{}", test_results.word_cnt()))];
-            let paragraph = Paragraph::new(text.iter()).block(block);
-            frame.render_widget(paragraph, row_1_chunks[0]);
-
-            let block = Block::default()
-                .title("characters typed")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, row_1_chunks[1]);
-            let text = [Text::raw(format!("{}", test_results.char_cnt()))];
-            let paragraph = Paragraph::new(text.iter()).block(block);
-            frame.render_widget(paragraph, row_1_chunks[1]);
-
-            let block = Block::default()
-                .title("total seconds")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, row_1_chunks[2]);
-            let text = [Text::raw(format!("{:.1}", test_results.duration_secs()))];
-            let paragraph = Paragraph::new(text.iter()).block(block);
-            frame.render_widget(paragraph, row_1_chunks[2]);
-
-            let word_durations = test_results.normalised_word_durations();
-            let block = Block::default()
-                .title("word times (normalised)")
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            frame.render_widget(block, rows[2]);
-            let sparkline = Sparkline::default().data(&word_durations).block(block);
-            frame.render_widget(sparkline, rows[2]);
-        })?;
+            })?;
+            if !carry_on {
+                break;
+            }
+        }
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
 
-        'hold: loop {
-            if let Event::Key(event) = read()? {
-                if event.code == KeyCode::Esc {
-                    break 'lessons;
-                } else if event.code == KeyCode::Enter {
-                    break 'hold;
+    if let Some(session) = race_session {
+        for past in session {
+            let test_words: VecDeque<&str> = past.words().iter().map(FinishedWord::value).collect();
+
+            let mode = config.test_mode(test_words.len());
+            let (_, carry_on) = run.run_mode(&mut terminal, "--race", |terminal| {
+                race_typing_test(
+                    terminal,
+                    &config,
+                    &theme,
+                    Some("racing your past self"),
+                    test_words,
+                    mode,
+                    &past.timeline(),
+                )
+            })?;
+            if !carry_on {
+                break;
+            }
+        }
+
+        leave_terminal(guard, &config, &run.compact_lines);
+        return;
+    }
+
+    // Everything typed in the lessons, bar pasted tests, for the session
+    // summary and heatmap.
+    let mut session = TestResults::from(vec![]);
+    let mut session_weak_words = vec![];
+    let mut quit_during = None;
+    let mut interrupted = false;
+    let mut next_lesson = if config.menu() {
+        menu::show_menu(&mut terminal, &theme, &lessons, &run.records, 0)?
+    } else {
+        Some(first_lesson)
+    };
+    'lessons: while let Some(lesson) = next_lesson {
+        let lesson_alphabet = &lessons[lesson];
+        let lesson_words = LessonWords::new(&config, &lessons, lesson, &word_list, &lengths);
+        let (candidates, unique) = (lesson_words.candidates, lesson_words.unique);
+        let mut test_words = lesson_words.pick();
+        let sentences;
+        if config.sentences {
+            sentences =
+                sentence::to_sentences(test_words.make_contiguous(), &mut rand::thread_rng());
+            test_words = sentences.iter().map(String::as_str).collect();
+        }
+        // It's worth saying when words are bound to repeat, and with
+        // `--no-repeats` how many there were to choose from.
+        let notice = if unique {
+            format!("{} different words can be typed in this lesson", candidates)
+        } else {
+            format!(
+                "only {} different words can be typed in this lesson, so they'll repeat",
+                candidates
+            )
+        };
+        if !show_splash(
+            &mut terminal,
+            &theme,
+            &test_words,
+            run.records.best(lesson_alphabet),
+            &config,
+            Some(notice.as_str()).filter(|_| unique || candidates < config.words),
+        )? {
+            if config.menu() {
+                next_lesson =
+                    menu::show_menu(&mut terminal, &theme, &lessons, &run.records, lesson)?;
+                continue 'lessons;
+            }
+            quit_during = Some(lesson);
+            break 'lessons;
+        }
+        let mode = config.test_mode(test_words.len());
+        let mut refill = || lesson_words.pick();
+        let (test_results, carry_on) =
+            run.run_mode(&mut terminal, lesson_alphabet, |terminal| {
+                if config.endless {
+                    endless_typing_test(
+                        terminal,
+                        &config,
+                        &theme,
+                        None,
+                        test_words,
+                        mode,
+                        &mut refill,
+                    )
+                } else {
+                    typing_test(
+                        terminal,
+                        &config,
+                        &theme,
+                        None,
+                        test_words,
+                        mode,
+                        Some(&mut refill),
+                    )
                 }
+            })?;
+
+        collect_weak_words(&mut session_weak_words, &test_results);
+        interrupted = test_results.interrupted();
+        if !test_results.pasted() {
+            session = session.merge(test_results.into_owned());
+        }
+        // Ctrl+C quits even from the menu.
+        next_lesson = if config.menu() && !interrupted {
+            menu::show_menu(&mut terminal, &theme, &lessons, &run.records, lesson)?
+        } else if carry_on {
+            match Some(lesson + 1).filter(|next| *next < lessons.len()) {
+                Some(next) => Some(next),
+                // Finishing the course goes round again if asked to, with the
+                // session's stats carrying on from this time round.
+                None if ask_again(&mut terminal, &theme)? => Some(0),
+                None => None,
+            }
+        } else {
+            quit_during = Some(lesson);
+            break 'lessons;
+        };
+    }
+
+    // Leave the previous session's weak words alone if nothing was typed.
+    if !session_weak_words.is_empty() {
+        WeakWords {
+            words: session_weak_words,
+        }
+        .save()?;
+    }
+
+    if let Some(path) = &config.export_heatmap {
+        fs::write(path, heatmap::to_svg(&session.key_stats()))?;
+    }
+
+    let confusions = session.typo_confusions();
+    // Ctrl+C is for getting out at once, not for waiting on a summary.
+    if !confusions.is_empty() && !interrupted {
+        show_session_summary(&mut terminal, &theme, &confusions)?;
+    }
+
+    // Lessons picked from the menu aren't a course to resume.
+    if !config.menu() {
+        match quit_during {
+            Some(lesson) => ResumePoint {
+                lesson: Some(lesson),
             }
+            .save()?,
+            None => ResumePoint::clear()?,
         }
     }
 
-    disable_raw_mode()?;
-    execute!(stdout(), LeaveAlternateScreen)?;
+    leave_terminal(guard, &config, &run.compact_lines);
 }
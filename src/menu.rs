@@ -0,0 +1,129 @@
+use crossterm::event::{read, Event, KeyCode};
+use fehler::throws;
+use tui::{
+    backend::Backend,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListState, Text},
+    Terminal,
+};
+
+use crate::{keymap, lessons, records::Records, theme::Theme, Error};
+
+/// One lesson's line in the menu, going by its `name` if it has one.
+fn menu_line(
+    idx: usize,
+    name: Option<&str>,
+    alphabet: &str,
+    best: Option<f64>,
+    mastered: bool,
+) -> String {
+    let lesson = match name {
+        Some(name) => format!("{} ({})", name, alphabet),
+        None => alphabet.to_string(),
+    };
+    let best = match best {
+        Some(wpm) => format!("best {:.0} wpm", wpm),
+        None => "not attempted".to_string(),
+    };
+    let mastered = if mastered { ", mastered" } else { "" };
+
+    format!("{:>2}. {}  {}{}", idx + 1, lesson, best, mastered)
+}
+
+/// The styles of the lessons in the menu and of the selected one, which
+/// stands out whatever the theme.
+fn list_styles(theme: &Theme) -> (Style, Style) {
+    let selected = theme
+        .untyped
+        .modifier(theme.untyped.modifier | Modifier::REVERSED);
+
+    (theme.preview, selected)
+}
+
+/// Let the user pick a lesson with the arrow keys and Enter, starting with
+/// `selected` highlighted. Returns `None` if they quit with Esc instead.
+#[throws]
+pub(crate) fn show_menu<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    lessons: &[String],
+    records: &Records,
+    mut selected: usize,
+) -> Option<usize> {
+    // The list scrolls itself to keep the selected lesson in view, remembering
+    // how far it's scrolled between draws.
+    let mut state = ListState::default();
+    loop {
+        state.select(Some(selected));
+        terminal.draw(|mut frame| {
+            let items = lessons.iter().enumerate().map(|(idx, alphabet)| {
+                Text::raw(menu_line(
+                    idx,
+                    lessons::name(alphabet),
+                    alphabet,
+                    records.best(alphabet),
+                    records.is_mastered(alphabet),
+                ))
+            });
+
+            let block = Block::default()
+                .title("lessons (↑/↓ to choose, Enter to start, Esc to quit)")
+                .borders(Borders::ALL)
+                .border_style(theme.border);
+            let (style, highlight_style) = list_styles(theme);
+            let list = List::new(items)
+                .block(block)
+                .style(style)
+                .highlight_style(highlight_style)
+                .highlight_symbol("> ");
+            frame.render_stateful_widget(list, frame.size(), &mut state);
+        })?;
+
+        if let Event::Key(event) = read()? {
+            if keymap::is_interrupt(&event) {
+                break None;
+            }
+            match event.code {
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Down => selected = (selected + 1).min(lessons.len() - 1),
+                KeyCode::Enter => break Some(selected),
+                KeyCode::Esc => break None,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::ThemeName;
+
+    #[test]
+    fn test_selected_lesson_stands_out() {
+        for name in [ThemeName::Default, ThemeName::HighContrast] {
+            let (style, highlight_style) = list_styles(&Theme::from(name));
+            assert_ne!(style, highlight_style, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_menu_line() {
+        assert_eq!(
+            menu_line(0, None, "aoeuhtns", Some(41.6), false),
+            " 1. aoeuhtns  best 42 wpm"
+        );
+        assert_eq!(
+            menu_line(11, None, "abc", None, false),
+            "12. abc  not attempted"
+        );
+        assert_eq!(
+            menu_line(1, None, "abc", Some(30.0), true),
+            " 2. abc  best 30 wpm, mastered"
+        );
+        assert_eq!(
+            menu_line(1, Some("Home row, 10 keys"), "aoeuidhtns", None, false),
+            " 2. Home row, 10 keys (aoeuidhtns)  not attempted"
+        );
+    }
+}
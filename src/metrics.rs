@@ -1,6 +1,9 @@
 use std::time::Duration;
 
-#[derive(Debug, Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum Metric {
     Delimiter {
@@ -16,4 +19,19 @@ pub enum Metric {
         expected: char,
         duration: Duration,
     },
+    Backspace {
+        duration: Duration,
+    },
+}
+
+impl Metric {
+    /// How long the keystroke took, since the one before it.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Delimiter { duration, .. }
+            | Self::Match { duration, .. }
+            | Self::Typo { duration, .. }
+            | Self::Backspace { duration } => *duration,
+        }
+    }
 }
@@ -0,0 +1,60 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// Short quotes to type, one per line with the author after a tab.
+const QUOTES: &str = include_str!("../assets/quotes.txt");
+
+/// Well-known passages that are the same for everyone, in the same format.
+const BENCHMARKS: &str = include_str!("../assets/benchmarks.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Quote {
+    pub text: &'static str,
+    pub author: &'static str,
+}
+
+/// Parse the quotes out of `contents`, skipping blank lines, `#` comments and
+/// anything without an author.
+fn parse(contents: &'static str) -> Vec<Quote> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '\t');
+            let text = parts.next()?.trim();
+            let author = parts.next()?.trim();
+            Some(Quote { text, author })
+        })
+        .collect()
+}
+
+/// Pick one of the built-in quotes at random.
+pub(crate) fn random<R: Rng>(rng: &mut R) -> Quote {
+    *parse(QUOTES)
+        .choose(rng)
+        .expect("the quotes asset is empty")
+}
+
+/// The built-in benchmark passages, in order.
+pub(crate) fn benchmarks() -> Vec<Quote> {
+    parse(BENCHMARKS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let quotes = parse("# comment\n\nShort and sweet.\tAnon\nno author\n");
+
+        assert_eq!(
+            quotes,
+            vec![Quote {
+                text: "Short and sweet.",
+                author: "Anon"
+            }]
+        );
+        assert!(!parse(QUOTES).is_empty());
+        assert_eq!(benchmarks().len(), 2);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs, io,
+    path::PathBuf,
+};
+
+use fehler::{throw, throws};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{history, scheduler::Schedule, Error};
+
+const RECORDS_FILE: &str = "records.json";
+const RESUME_FILE: &str = "resume.json";
+const WEAK_WORDS_FILE: &str = "weak_words.json";
+
+/// The directory dvors keeps its persistent state in, following the XDG base
+/// directory spec (`$XDG_DATA_HOME/dvors`, falling back to `~/.local/share/dvors`).
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME") {
+        return Some(PathBuf::from(dir).join("dvors"));
+    }
+
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share/dvors"))
+}
+
+/// Load `file` from the data directory, starting afresh if it doesn't exist.
+/// One that can't be parsed is an error, rather than something to be
+/// overwritten with nothing the next time it's saved.
+#[throws]
+pub(crate) fn load<T: Default + DeserializeOwned>(file: &str) -> T {
+    let path = match data_dir() {
+        Some(dir) => dir.join(file),
+        None => return T::default(),
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(source) => throw!(Error::DataError { path, source }),
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => T::default(),
+        Err(err) => throw!(err),
+    }
+}
+
+/// Save `value` as `file` in the data directory. It's written to a temporary
+/// file that's then renamed over the old one, so that being interrupted
+/// partway through can't leave it cut short.
+#[throws]
+pub(crate) fn save<T: Serialize>(file: &str, value: &T) {
+    if let Some(dir) = data_dir() {
+        fs::create_dir_all(&dir)?;
+        let temp = dir.join(format!("{}.tmp", file));
+        fs::write(&temp, serde_json::to_string_pretty(value)?)?;
+        fs::rename(temp, dir.join(file))?;
+    }
+}
+
+#[throws]
+pub(crate) fn remove(file: &str) {
+    if let Some(dir) = data_dir() {
+        match fs::remove_file(dir.join(file)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => throw!(err),
+        }
+    }
+}
+
+/// Personal bests, keyed by the lesson alphabet so that reordering or adding
+/// lessons doesn't shuffle the records around. The other modes are keyed by
+/// the flag they're taken with.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Records {
+    best_wpm: BTreeMap<String, f64>,
+    /// The lessons passed with `--mastery`.
+    #[serde(default)]
+    mastered: BTreeSet<String>,
+}
+
+impl Records {
+    #[throws]
+    pub fn load() -> Self {
+        load(RECORDS_FILE)?
+    }
+
+    #[throws]
+    pub fn save(&self) {
+        save(RECORDS_FILE, self)?;
+    }
+
+    pub fn best(&self, lesson: &str) -> Option<f64> {
+        self.best_wpm.get(lesson).copied()
+    }
+
+    /// Record `wpm` for `lesson`, returning whether it beat the previous best.
+    pub fn update(&mut self, lesson: &str, wpm: f64) -> bool {
+        match self.best_wpm.get(lesson) {
+            Some(best) if *best >= wpm => false,
+            _ => {
+                self.best_wpm.insert(lesson.to_string(), wpm);
+                true
+            }
+        }
+    }
+
+    pub fn is_mastered(&self, lesson: &str) -> bool {
+        self.mastered.contains(lesson)
+    }
+
+    /// Record that `lesson` was mastered, returning whether it hadn't been
+    /// before.
+    pub fn master(&mut self, lesson: &str) -> bool {
+        self.mastered.insert(lesson.to_string())
+    }
+}
+
+/// The lesson to pick up from when the previous session was quit early.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ResumePoint {
+    pub lesson: Option<usize>,
+}
+
+impl ResumePoint {
+    #[throws]
+    pub fn load() -> Self {
+        load(RESUME_FILE)?
+    }
+
+    #[throws]
+    pub fn save(&self) {
+        save(RESUME_FILE, self)?;
+    }
+
+    #[throws]
+    pub fn clear() {
+        remove(RESUME_FILE)?;
+    }
+}
+
+/// The words that went worst in the most recent session, for drilling.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct WeakWords {
+    pub words: Vec<String>,
+}
+
+impl WeakWords {
+    #[throws]
+    pub fn load() -> Self {
+        load(WEAK_WORDS_FILE)?
+    }
+
+    #[throws]
+    pub fn save(&self) {
+        save(WEAK_WORDS_FILE, self)?;
+    }
+}
+
+/// Delete the saved records, resume point, weak words, review schedule and
+/// history, if there are any.
+#[throws]
+pub(crate) fn reset() {
+    remove(RECORDS_FILE)?;
+    remove(WEAK_WORDS_FILE)?;
+    ResumePoint::clear()?;
+    Schedule::clear()?;
+    history::clear()?;
+}
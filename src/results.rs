@@ -0,0 +1,714 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+use clap::ValueEnum;
+use crossterm::event::{poll, read, Event, KeyCode};
+use fehler::throws;
+use serde::Serialize;
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge, Paragraph, Sparkline, Text},
+    Terminal,
+};
+
+use crate::{
+    config::{Config, Settings},
+    keymap,
+    theme::Theme,
+    typingtest::{Confusions, TestResults},
+    widgets::{self, GoalSparkline},
+    word::{CharDiff, FinishedWord},
+    Error,
+};
+
+/// The aggregate statistics of a test, for printing outside of the TUI.
+#[derive(Debug, Serialize)]
+pub(crate) struct Summary {
+    wpm: f64,
+    cps: f64,
+    typos: usize,
+    words: usize,
+    clean_streak: usize,
+    characters: usize,
+    keystrokes: usize,
+    seconds: f64,
+    avg_reaction_ms: u128,
+    rhythm_jitter: f64,
+    slowest_trigrams: Vec<(String, u128)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    settings: Option<Settings>,
+}
+
+impl From<&TestResults<'_>> for Summary {
+    fn from(test_results: &TestResults) -> Self {
+        Self {
+            wpm: test_results.wpm_avg(),
+            cps: test_results.cps(),
+            typos: test_results.typo_cnt(),
+            words: test_results.word_cnt(),
+            clean_streak: test_results.longest_clean_word_streak(),
+            characters: test_results.char_cnt(),
+            keystrokes: test_results.total_keystrokes(),
+            seconds: test_results.duration_secs(),
+            avg_reaction_ms: test_results.avg_word_initiation().as_millis(),
+            rhythm_jitter: test_results.rhythm_jitter(),
+            slowest_trigrams: test_results
+                .slowest_trigrams(3)
+                .into_iter()
+                .map(|((a, b, c), latency)| ([a, b, c].iter().collect(), latency.as_millis()))
+                .collect(),
+            settings: test_results.settings().cloned(),
+        }
+    }
+}
+
+/// How `value` is written on the command line.
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(settings) = &self.settings {
+            writeln!(
+                f,
+                "settings: {}, {} number row, {} words, {} delimiter, {} difficulty, {}% target accuracy",
+                settings.layout,
+                value_name(settings.number_row),
+                settings.words,
+                value_name(settings.delimiter),
+                value_name(settings.difficulty),
+                settings.target_accuracy
+            )?;
+        }
+        writeln!(f, "wpm: {:.0} ({:.1} cps)", self.wpm, self.cps)?;
+        writeln!(f, "typos: {}", self.typos)?;
+        writeln!(f, "words typed: {}", self.words)?;
+        writeln!(f, "clean streak: {} words", self.clean_streak)?;
+        writeln!(f, "characters typed: {}", self.characters)?;
+        writeln!(f, "total keystrokes: {}", self.keystrokes)?;
+        writeln!(f, "total seconds: {:.1}", self.seconds)?;
+        writeln!(f, "avg reaction: {}ms", self.avg_reaction_ms)?;
+        writeln!(f, "rhythm: {:.2} jitter", self.rhythm_jitter)?;
+        write!(f, "slowest trigrams:")?;
+        for (trigram, latency) in &self.slowest_trigrams {
+            write!(f, " {:?} {}ms", trigram, latency)?;
+        }
+        Ok(())
+    }
+}
+
+/// The headline numbers of a test on one line, for `--compact-results` and
+/// `--print-results`.
+pub(crate) fn compact_line(test_results: &TestResults) -> String {
+    format!(
+        "{:.0} wpm · {:.0}% · {} typos · {:.0}s",
+        test_results.wpm_avg(),
+        test_results.accuracy(),
+        test_results.typo_cnt(),
+        test_results.duration_secs()
+    )
+}
+
+/// The personal best a test is measured against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PersonalBest {
+    FirstAttempt,
+    Previous(f64),
+}
+
+/// Show the results of a test, returning `true` to carry on (Enter) or `false`
+/// to quit (Esc). The words can be inspected one by one by pressing `w`.
+#[throws]
+pub(crate) fn show_results<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    config: &Config,
+    test_results: &TestResults,
+    best: PersonalBest,
+) -> bool {
+    // Ctrl+C quits straight away.
+    if test_results.interrupted() {
+        return false;
+    }
+    let mut word_list_scroll = None;
+
+    let shown_at = SystemTime::now();
+
+    loop {
+        // The accuracy gauge fills up when the results first appear.
+        let gauge_fill = if config.no_animation || config.compact_results {
+            1.0
+        } else {
+            (shown_at.elapsed()?.as_secs_f64() / GAUGE_FILL.as_secs_f64()).min(1.0)
+        };
+
+        match word_list_scroll {
+            Some(scroll) => draw_word_list(terminal, theme, test_results, scroll)?,
+            None if config.compact_results => draw_compact(terminal, theme, config, test_results)?,
+            None => draw_results(terminal, theme, config, test_results, best, gauge_fill)?,
+        }
+
+        if gauge_fill < 1.0 && !poll(TICK)? {
+            continue;
+        }
+
+        if let Event::Key(event) = read()? {
+            let last_word = test_results.word_cnt().saturating_sub(1) as u16;
+            match (event.code, word_list_scroll) {
+                _ if keymap::is_interrupt(&event) => break false,
+                (KeyCode::Esc, None) => break false,
+                (KeyCode::Enter, None) => break true,
+                (KeyCode::Char('w'), None) => word_list_scroll = Some(0),
+                (KeyCode::Esc, Some(_)) | (KeyCode::Char('w'), Some(_)) => word_list_scroll = None,
+                (KeyCode::Up, Some(scroll)) => word_list_scroll = Some(scroll.saturating_sub(1)),
+                (KeyCode::Down, Some(scroll)) => {
+                    word_list_scroll = Some((scroll + 1).min(last_word))
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// How often to redraw while the accuracy gauge is filling.
+const TICK: Duration = Duration::from_millis(20);
+
+/// How long the accuracy gauge takes to fill.
+const GAUGE_FILL: Duration = Duration::from_millis(500);
+
+/// The smallest terminal the results grid is legible in, with the row of key
+/// stats at its tallest.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 27;
+
+/// The percentage of keystrokes one hand can make before we point out that the
+/// typing was lopsided.
+const LOPSIDED: f64 = 65.0;
+
+/// The confusions to show at the end of a session.
+const TOP_CONFUSIONS: usize = 10;
+
+/// The keys with the most typos to list under a test's typos.
+const TOP_TYPO_KEYS: usize = 3;
+
+/// The `n` most frequent confusions, most frequent first.
+fn top_confusions(confusions: &Confusions, n: usize) -> Vec<((char, char), usize)> {
+    let mut top = confusions
+        .iter()
+        .map(|(pair, cnt)| (*pair, *cnt))
+        .collect::<Vec<_>>();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top.truncate(n);
+
+    top
+}
+
+/// Show the keys most often confused with one another over the whole session,
+/// waiting for any key to dismiss it.
+#[throws]
+pub(crate) fn show_session_summary<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    confusions: &Confusions,
+) {
+    terminal.draw(|mut frame| {
+        let text = top_confusions(confusions, TOP_CONFUSIONS)
+            .into_iter()
+            .flat_map(|((expected, typed), cnt)| {
+                vec![
+                    Text::styled(expected.to_string(), theme.correct),
+                    Text::raw(" typed as "),
+                    Text::styled(typed.to_string(), theme.incorrect),
+                    Text::raw(format!(" {}×\n", cnt)),
+                ]
+            })
+            .collect::<Vec<Text>>();
+
+        let block = Block::default()
+            .title("most confused keys this session (any key to exit)")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, frame.size());
+    })?;
+
+    while !matches!(read()?, Event::Key(_)) {}
+}
+
+/// A bar `width` characters long filled in to `share` of the way, between 0
+/// and 1.
+fn share_bar(share: f64, width: usize) -> String {
+    let filled = (share * width as f64).round() as usize;
+
+    "█".repeat(filled) + &"░".repeat(width.saturating_sub(filled))
+}
+
+/// Ask whether to practise the lessons again, returning `true` for y and
+/// `false` for n or Esc.
+#[throws]
+pub(crate) fn ask_again<B: Backend>(terminal: &mut Terminal<B>, theme: &Theme) -> bool {
+    terminal.draw(|mut frame| {
+        let text = [Text::styled(
+            "That's every lesson done! Practise again? (y/n)",
+            theme.untyped,
+        )];
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, frame.size());
+    })?;
+
+    loop {
+        if let Event::Key(event) = read()? {
+            match event.code {
+                _ if keymap::is_interrupt(&event) => break false,
+                KeyCode::Char('y') | KeyCode::Char('Y') => break true,
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => break false,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Render each word with its characters coloured by how they were typed.
+fn diff_text<'a>(word: &FinishedWord, theme: &Theme) -> Vec<Text<'a>> {
+    let mut text = word
+        .diff()
+        .into_iter()
+        .map(|char_diff| match char_diff {
+            CharDiff::Correct(c) => Text::styled(c.to_string(), theme.correct),
+            CharDiff::Substituted { typed, .. } | CharDiff::Extra(typed) => {
+                Text::styled(typed.to_string(), theme.incorrect)
+            }
+            CharDiff::Missing(_) => Text::styled("_", theme.preview),
+        })
+        .collect::<Vec<Text>>();
+
+    if word.skipped() {
+        text.push(Text::styled(" (skipped)", theme.preview));
+    }
+    if let Some(delimiter) = word.delimiter() {
+        let c = match delimiter {
+            ' ' => '␣',
+            '\n' => '⏎',
+            _ => delimiter,
+        };
+        text.push(Text::styled(c.to_string(), theme.typed));
+    }
+    text.push(Text::raw("\n"));
+
+    text
+}
+
+#[throws]
+fn draw_word_list<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    test_results: &TestResults,
+    scroll: u16,
+) {
+    terminal.draw(|mut frame| {
+        let text = test_results
+            .words()
+            .iter()
+            .flat_map(|word| diff_text(word, theme))
+            .collect::<Vec<Text>>();
+
+        let block = Block::default()
+            .title("words (↑/↓ to scroll, w to go back)")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        let paragraph = Paragraph::new(text.iter()).block(block).scroll(scroll);
+        frame.render_widget(paragraph, frame.size());
+    })?;
+}
+
+/// Draw just the `compact_line` in place of the results grid, in the correct
+/// colour if the target accuracy was met and the incorrect one if not.
+#[throws]
+fn draw_compact<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    config: &Config,
+    test_results: &TestResults,
+) {
+    terminal.draw(|mut frame| {
+        let style = if test_results.accuracy() >= config.target_accuracy {
+            theme.correct
+        } else {
+            theme.incorrect
+        };
+        let text = [Text::styled(compact_line(test_results), style)];
+        let paragraph = Paragraph::new(text.iter());
+        frame.render_widget(paragraph, frame.size());
+    })?;
+}
+
+#[throws]
+fn draw_results<B: Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    config: &Config,
+    test_results: &TestResults,
+    best: PersonalBest,
+    gauge_fill: f64,
+) {
+    terminal.draw(|mut frame| {
+        if !widgets::fits(&mut frame, MIN_WIDTH, MIN_HEIGHT) {
+            return;
+        }
+
+        // A pasted test gets a banner across the top, as nothing else in it
+        // counts.
+        let banner_height = if test_results.pasted() { 1 } else { 0 };
+
+        // Stats about the keys that need more room than a cell, a line each.
+        let mut keys_text = vec![];
+        if test_results.total_keystrokes() > 0 {
+            keys_text.push(Text::raw(format!(
+                "home row: {:.0}%\n",
+                test_results.row_distribution()[2]
+            )));
+            keys_text.push(Text::raw(format!(
+                "finger travel: {:.0} key-units\n",
+                test_results.finger_travel()
+            )));
+        }
+        let (left, right) = test_results.hand_balance();
+        if left + right > 0 {
+            let left_pct = (left as f64 / (left + right) as f64 * 100.0).round();
+            keys_text.push(Text::raw(format!(
+                "hand balance: {:.0}% L / {:.0}% R\n",
+                left_pct,
+                100.0 - left_pct
+            )));
+            if !(100.0 - LOPSIDED..=LOPSIDED).contains(&left_pct) {
+                keys_text.push(Text::styled(
+                    "lopsided, try to share the work between hands\n",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+        }
+        if let Some((accuracy, latency)) = test_results.double_letter_stats() {
+            keys_text.push(Text::raw(format!(
+                "doubled letters: {:.0}% accuracy, {}ms\n",
+                accuracy,
+                latency.as_millis()
+            )));
+        }
+        let keys_height = match keys_text.len() {
+            0 => 0,
+            lines => lines as u16 + 2,
+        };
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(banner_height),
+                    Constraint::Length(3),
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(30),
+                    Constraint::Length(keys_height),
+                    Constraint::Percentage(40),
+                ]
+                .as_ref(),
+            )
+            .split(frame.size());
+
+        let banner = [Text::styled(
+            "paste detected, this test doesn't count",
+            Style::default().fg(Color::Red),
+        )];
+        let paragraph = Paragraph::new(banner.iter()).alignment(Alignment::Center);
+        frame.render_widget(paragraph, rows[0]);
+
+        let accuracy = test_results.accuracy();
+        let label = format!("accuracy {:.0}%", accuracy * gauge_fill);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        let gauge = Gauge::default()
+            .block(block)
+            .style(theme.correct)
+            .ratio((accuracy / 100.0 * gauge_fill).clamp(0.0, 1.0))
+            .label(&label);
+        frame.render_widget(gauge, rows[1]);
+
+        let row_0_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                ]
+                .as_ref(),
+            )
+            .split(rows[2]);
+
+        let row_1_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(25),
+                ]
+                .as_ref(),
+            )
+            .split(rows[3]);
+
+        let block = Block::default()
+            .title("wpm")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_0_chunks[0]);
+        let wpm = test_results.wpm_avg();
+        let mut text = vec![Text::raw(format!(
+            "{:.0} ({:.1} cps)\nraw {:.0} / net {:.0}\n",
+            wpm,
+            test_results.cps(),
+            test_results.raw_wpm(),
+            test_results.net_wpm()
+        ))];
+        match best {
+            // The banner says why there's nothing to compare.
+            _ if test_results.pasted() => {}
+            _ if config.mastery => {
+                let target = config.target_wpm.unwrap_or_default() as f64;
+                if test_results.mastered(target) {
+                    text.push(Text::styled("MASTERED", Style::default().fg(Color::Green)));
+                } else {
+                    text.push(Text::styled("FAILED", Style::default().fg(Color::Red)));
+                }
+            }
+            PersonalBest::FirstAttempt => text.push(Text::raw("first attempt")),
+            PersonalBest::Previous(best) => {
+                let color = if wpm >= best {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                text.push(Text::styled(
+                    format!("{:+.0} wpm vs your best", wpm - best),
+                    Style::default().fg(color),
+                ));
+                if wpm > best {
+                    text.push(Text::styled(
+                        "\nnew personal best!",
+                        Style::default().fg(Color::Green),
+                    ));
+                }
+            }
+        }
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_0_chunks[0]);
+
+        let block = Block::default()
+            .title("typos")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_0_chunks[1]);
+        let (dropped, extra) = test_results.length_errors();
+        let mut text = vec![Text::raw(format!(
+            "{}\ndropped: {} / extra: {}",
+            test_results.typo_cnt(),
+            dropped,
+            extra
+        ))];
+        let worst_keys = test_results
+            .typo_counts_by_expected()
+            .into_iter()
+            .take(TOP_TYPO_KEYS)
+            .map(|(c, cnt)| format!("{} {}", if c == ' ' { '␣' } else { c }, cnt))
+            .collect::<Vec<_>>();
+        if !worst_keys.is_empty() {
+            text.push(Text::raw(format!(
+                "\nworst keys: {}",
+                worst_keys.join(", ")
+            )));
+        }
+        let streak = test_results.max_typo_streak();
+        if streak > 0 {
+            text.push(Text::raw(format!("\nworst typo streak: {}", streak)));
+        }
+        if let Some(accuracy) = test_results.shifted_accuracy(config.number_row) {
+            text.push(Text::raw(format!(
+                "\nshifted keys: {:.0}% accuracy",
+                accuracy
+            )));
+        }
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_0_chunks[1]);
+
+        let block = Block::default()
+            .title("slowest trigrams")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_0_chunks[2]);
+        let text = test_results
+            .slowest_trigrams(3)
+            .into_iter()
+            .map(|((a, b, c), latency)| {
+                let trigram = [a, b, c]
+                    .iter()
+                    .map(|c| if *c == ' ' { '␣' } else { *c })
+                    .collect::<String>();
+                Text::raw(format!("{} {}ms\n", trigram, latency.as_millis()))
+            })
+            .collect::<Vec<Text>>();
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_0_chunks[2]);
+
+        let block = Block::default()
+            .title("median word")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_0_chunks[3]);
+        let text = [Text::raw(format!(
+            "{:.2}s",
+            test_results.median_word_duration_secs()
+        ))];
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_0_chunks[3]);
+
+        let block = Block::default()
+            .title("words typed [w]")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_1_chunks[0]);
+        let text = [Text::raw(format!(
+            "{}\nclean streak: {} words",
+            test_results.word_cnt(),
+            test_results.longest_clean_word_streak()
+        ))];
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_1_chunks[0]);
+
+        let block = Block::default()
+            .title("characters typed")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_1_chunks[1]);
+        let text = [Text::raw(format!(
+            "{}\ntotal keystrokes: {}",
+            test_results.char_cnt(),
+            test_results.total_keystrokes()
+        ))];
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_1_chunks[1]);
+
+        let block = Block::default()
+            .title("total seconds")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_1_chunks[2]);
+        let mut text = vec![Text::raw(format!("{:.1}", test_results.duration_secs()))];
+        let breakdown = test_results.time_breakdown();
+        let total = breakdown.total().as_secs_f64();
+        if total > 0.0 {
+            let bar_width = usize::from(block.inner(row_1_chunks[2]).width).saturating_sub(13);
+            for (label, duration, style) in [
+                ("typing", breakdown.typing, theme.correct),
+                ("idle", breakdown.idle, theme.preview),
+                ("fixing", breakdown.correcting, theme.incorrect),
+            ] {
+                let share = duration.as_secs_f64() / total;
+                text.push(Text::raw(format!("\n{:<6} ", label)));
+                text.push(Text::styled(share_bar(share, bar_width), style));
+                text.push(Text::raw(format!(" {:>3.0}%", share * 100.0)));
+            }
+        }
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_1_chunks[2]);
+
+        let block = Block::default()
+            .title("avg reaction")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_1_chunks[3]);
+        let text = [Text::raw(format!(
+            "{}ms\nrhythm: {:.2} jitter, {:.0}% consistent",
+            test_results.avg_word_initiation().as_millis(),
+            test_results.rhythm_jitter(),
+            test_results.consistency()
+        ))];
+        let paragraph = Paragraph::new(text.iter()).block(block);
+        frame.render_widget(paragraph, row_1_chunks[3]);
+
+        if keys_height > 0 {
+            let block = Block::default()
+                .title("keys")
+                .borders(Borders::ALL)
+                .border_style(theme.border);
+            let paragraph = Paragraph::new(keys_text.iter()).block(block).wrap(true);
+            frame.render_widget(paragraph, rows[4]);
+        }
+
+        let row_2_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(rows[5]);
+
+        let timing_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(75), Constraint::Percentage(25)].as_ref())
+            .split(row_2_chunks[0]);
+
+        let word_durations = test_results.normalised_word_durations();
+        let block = Block::default()
+            .title("word times (normalised)")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, timing_chunks[0]);
+        let sparkline = Sparkline::default().data(&word_durations).block(block);
+        frame.render_widget(sparkline, timing_chunks[0]);
+
+        let latencies = test_results
+            .latency_by_position()
+            .iter()
+            .map(|latency| latency.as_millis() as u64)
+            .collect::<Vec<u64>>();
+        let block = Block::default()
+            .title("latency by letter")
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, timing_chunks[1]);
+        let sparkline = Sparkline::default().data(&latencies).block(block);
+        frame.render_widget(sparkline, timing_chunks[1]);
+
+        let (word_wpms, title) = if config.clean_sparkline {
+            (
+                test_results.clean_word_wpm(),
+                "word wpm (words without typos)",
+            )
+        } else {
+            (test_results.per_word_wpm(), "word wpm")
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme.border);
+        frame.render_widget(block, row_2_chunks[1]);
+        let word_millis = test_results.word_millis(config.clean_sparkline);
+        let sparkline = GoalSparkline::new(&word_wpms)
+            .widths(Some(word_millis.as_slice()).filter(|_| config.time_axis))
+            .goal(config.target_wpm)
+            .below_style(Style::default().fg(Color::Red))
+            .goal_style(Style::default().fg(Color::Yellow))
+            .block(block);
+        frame.render_widget(sparkline, row_2_chunks[1]);
+    })?;
+}
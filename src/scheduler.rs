@@ -0,0 +1,149 @@
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use fehler::throws;
+use serde::{Deserialize, Serialize};
+
+use crate::{records, Error};
+
+const SCHEDULE_FILE: &str = "schedule.json";
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The ease a word starts with, as in SM-2.
+const INITIAL_EASE: f64 = 2.5;
+
+/// Below this, words would come round so often they'd never get easier.
+const MIN_EASE: f64 = 1.3;
+
+/// The SM-2 grades (out of 5) given to a word typed cleanly and a word with a
+/// typo in it. Anything below 3 counts as forgotten.
+const CLEAN_GRADE: f64 = 5.0;
+const MISSED_GRADE: f64 = 2.0;
+
+/// The number of whole days since the Unix epoch, which is as finely as
+/// reviews are scheduled.
+#[throws]
+pub(crate) fn today() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / SECS_PER_DAY
+}
+
+/// How well a word is known, and so when it's next due for review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Card {
+    ease: f64,
+    /// Days until the next review.
+    interval: u64,
+    /// How many reviews in a row the word has been typed cleanly.
+    repetitions: u32,
+    /// The day the word is next due.
+    due: u64,
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self {
+            ease: INITIAL_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+        }
+    }
+}
+
+/// When each word that's been reviewed is due to come round again, following
+/// the SM-2 spaced repetition algorithm.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Schedule {
+    cards: BTreeMap<String, Card>,
+}
+
+impl Schedule {
+    #[throws]
+    pub fn load() -> Self {
+        records::load(SCHEDULE_FILE)?
+    }
+
+    #[throws]
+    pub fn save(&self) {
+        records::save(SCHEDULE_FILE, self)?;
+    }
+
+    #[throws]
+    pub fn clear() {
+        records::remove(SCHEDULE_FILE)?;
+    }
+
+    /// Whether `word` has been reviewed before.
+    pub fn contains(&self, word: &str) -> bool {
+        self.cards.contains_key(word)
+    }
+
+    /// The words due for review on `today`, most overdue first.
+    pub fn due(&self, today: u64) -> Vec<&str> {
+        let mut due = self
+            .cards
+            .iter()
+            .filter(|(_, card)| card.due <= today)
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(_, card)| card.due);
+
+        due.into_iter().map(|(word, _)| word.as_str()).collect()
+    }
+
+    /// Reschedule `word` after it was reviewed on `today`. Clean words come
+    /// round after longer and longer intervals, while missed ones start over.
+    pub fn review(&mut self, word: &str, clean: bool, today: u64) {
+        let card = self.cards.entry(word.to_string()).or_default();
+        let grade = if clean { CLEAN_GRADE } else { MISSED_GRADE };
+
+        if clean {
+            card.interval = match card.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (card.interval as f64 * card.ease).round() as u64,
+            };
+            card.repetitions += 1;
+        } else {
+            card.interval = 1;
+            card.repetitions = 0;
+        }
+        card.ease = (card.ease + 0.1 - (5.0 - grade) * (0.08 + (5.0 - grade) * 0.02)).max(MIN_EASE);
+        card.due = today + card.interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review() {
+        let mut schedule = Schedule::default();
+        schedule.review("word", true, 10);
+        assert_eq!(schedule.due(10), Vec::<&str>::new());
+        assert_eq!(schedule.due(11), vec!["word"]);
+
+        schedule.review("word", true, 11);
+        assert_eq!(schedule.cards["word"].due, 17);
+        schedule.review("word", true, 17);
+        assert_eq!(schedule.cards["word"].interval, 16);
+
+        schedule.review("word", false, 33);
+        let card = &schedule.cards["word"];
+        assert_eq!((card.interval, card.repetitions, card.due), (1, 0, 34));
+        assert!((card.ease - 2.48).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_due_order() {
+        let mut schedule = Schedule::default();
+        schedule.review("later", true, 5);
+        schedule.review("sooner", false, 1);
+        schedule.review("future", true, 50);
+
+        assert_eq!(schedule.due(10), vec!["sooner", "later"]);
+    }
+}
@@ -0,0 +1,73 @@
+use rand::Rng;
+
+const MIN_SENTENCE_LEN: usize = 3;
+const MAX_SENTENCE_LEN: usize = 8;
+
+/// Assemble sampled words into pseudo-sentences, capitalising the first word
+/// of each and ending the last with a full stop, e.g. `["The", "cat", "ran."]`.
+pub(crate) fn to_sentences<R: Rng>(words: &[&str], rng: &mut R) -> Vec<String> {
+    let mut sentences = Vec::with_capacity(words.len());
+
+    let mut remaining = words;
+    while !remaining.is_empty() {
+        let len = rng
+            .gen_range(MIN_SENTENCE_LEN, MAX_SENTENCE_LEN + 1)
+            .min(remaining.len());
+        let (sentence, rest) = remaining.split_at(len);
+        remaining = rest;
+
+        for (idx, word) in sentence.iter().enumerate() {
+            let mut word = if idx == 0 {
+                capitalise(word)
+            } else {
+                word.to_string()
+            };
+            if idx == len - 1 {
+                word.push('.');
+            }
+            sentences.push(word);
+        }
+    }
+
+    sentences
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_sentences_are_capitalised_and_terminated() {
+        let words = [
+            "the", "cat", "ran", "to", "the", "shop", "and", "back", "again",
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let sentences = to_sentences(&words, &mut rng);
+
+        assert_eq!(sentences.len(), words.len());
+        assert!(sentences[0].starts_with('T'));
+        assert!(sentences.last().unwrap().ends_with('.'));
+        for pair in sentences.windows(2) {
+            if pair[0].ends_with('.') {
+                assert!(pair[1].chars().next().unwrap().is_uppercase());
+            }
+        }
+    }
+
+    #[test]
+    fn test_capitalise() {
+        assert_eq!(capitalise("dvorak"), "Dvorak");
+        assert_eq!(capitalise(""), "");
+    }
+}
@@ -0,0 +1,120 @@
+use clap::ValueEnum;
+use tui::style::{Color, Modifier, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum ThemeName {
+    Default,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Theme {
+    /// Words that have already been typed.
+    pub typed: Style,
+    /// Correctly typed characters of the current word.
+    pub correct: Style,
+    /// Incorrectly typed characters of the current word.
+    pub incorrect: Style,
+    /// Correctly typed characters of the current word that took too long.
+    pub slow: Style,
+    /// Characters of the current word still to be typed.
+    pub untyped: Style,
+    /// Upcoming words.
+    pub preview: Style,
+    pub border: Style,
+    /// Added to the character the pace ghost has reached.
+    pub ghost: Modifier,
+    /// Added to the character to be typed next.
+    pub cursor: Modifier,
+    /// Words that have already been typed, in focus mode.
+    pub focus_typed: Style,
+    /// Upcoming words, in focus mode.
+    pub focus_preview: Style,
+    /// Characters of the current word still to be typed, in focus mode.
+    pub focus_untyped: Style,
+    /// Added to the whole of the current word in focus mode.
+    pub focus: Modifier,
+    /// Added to the whole of the current word, so that it stands out as one
+    /// from the words around it.
+    pub active: Modifier,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            typed: Style::default().fg(Color::DarkGray),
+            correct: Style::default().fg(Color::Blue),
+            incorrect: Style::default().fg(Color::Red),
+            slow: Style::default().fg(Color::Yellow),
+            untyped: Style::default(),
+            preview: Style::default().fg(Color::DarkGray),
+            border: Style::default().fg(Color::DarkGray),
+            ghost: Modifier::UNDERLINED,
+            cursor: Modifier::REVERSED,
+            focus_typed: Style::default().fg(Color::DarkGray).modifier(Modifier::DIM),
+            focus_preview: Style::default().fg(Color::Gray).modifier(Modifier::DIM),
+            focus_untyped: Style::default().fg(Color::White),
+            focus: Modifier::BOLD,
+            active: Modifier::BOLD,
+        }
+    }
+}
+
+impl Theme {
+    /// Bright, clearly distinct colours for terminals (and eyes) where the
+    /// default dark grey is hard to make out.
+    fn high_contrast() -> Self {
+        Self {
+            typed: Style::default().fg(Color::Gray),
+            correct: Style::default()
+                .fg(Color::LightGreen)
+                .modifier(Modifier::BOLD),
+            incorrect: Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .modifier(Modifier::BOLD),
+            slow: Style::default()
+                .fg(Color::LightYellow)
+                .modifier(Modifier::BOLD),
+            untyped: Style::default().fg(Color::White).modifier(Modifier::BOLD),
+            preview: Style::default().fg(Color::White),
+            border: Style::default().fg(Color::White),
+            ghost: Modifier::UNDERLINED | Modifier::REVERSED,
+            cursor: Modifier::UNDERLINED | Modifier::BOLD,
+            focus_typed: Style::default().fg(Color::Gray).modifier(Modifier::ITALIC),
+            focus_preview: Style::default().fg(Color::Gray),
+            focus_untyped: Style::default().fg(Color::White),
+            focus: Modifier::BOLD | Modifier::UNDERLINED,
+            active: Modifier::BOLD,
+        }
+    }
+
+    /// `style` with the active word's emphasis added.
+    pub fn active(&self, style: Style) -> Style {
+        style.modifier(style.modifier | self.active)
+    }
+
+    /// The theme with the focus styles swapped in, dimming everything but the
+    /// current word and making that stand out.
+    pub fn focused(&self) -> Self {
+        let emphasise = |style: Style| style.modifier(style.modifier | self.focus);
+
+        Self {
+            typed: self.focus_typed,
+            preview: self.focus_preview,
+            correct: emphasise(self.correct),
+            incorrect: emphasise(self.incorrect),
+            untyped: emphasise(self.focus_untyped),
+            ..*self
+        }
+    }
+}
+
+impl From<ThemeName> for Theme {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self::default(),
+            ThemeName::HighContrast => Self::high_contrast(),
+        }
+    }
+}
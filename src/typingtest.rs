@@ -1,161 +1,2631 @@
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
+    iter::FromIterator,
+    mem,
     time::{Duration, SystemTime},
 };
 
-use crossterm::event::{read, Event, KeyCode};
-use fehler::throws;
+use crossterm::event::{poll, read, Event, KeyCode};
+use fehler::{throw, throws};
+use serde::{Deserialize, Serialize};
 use tui::{
-    style::{Color, Style},
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
     widgets::{Block, Borders, Paragraph, Text},
     Terminal,
 };
 
 use crate::{
+    compose::Composer,
+    config::{Config, Delimiter, Settings, TestMode},
+    hands::{self, Hand},
+    heatmap::{self, KeyStats},
+    keyboard::{self, Keyboard, NumberRow},
     keymap,
     metrics::Metric,
-    word::{FinishedWord, Word},
+    theme::Theme,
+    widgets,
+    word::{FinishedWord, RenderOptions, Word},
     Error,
 };
 
-pub struct TestResults<'a>(Vec<FinishedWord<'a>>);
+/// How often the test redraws while waiting for input, to keep anything that
+/// moves on its own (like the pace ghost) moving smoothly.
+const TICK: Duration = Duration::from_millis(50);
+
+/// The smallest terminal there's room to type in: a line of text inside the
+/// border, and the stats line below it.
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 4;
+
+/// The keyboard's four rows, and its border.
+const KEYBOARD_HEIGHT: u16 = 6;
+
+/// How long before the end of a timed test the time left turns red.
+const FINAL_SECONDS: Duration = Duration::from_secs(10);
+
+/// How long a blinking cursor stays on, and then off, for.
+const BLINK: Duration = Duration::from_millis(500);
+
+/// Keystrokes closer together than this can't have been typed by hand.
+const PASTE_GAP: Duration = Duration::from_millis(2);
+
+/// How many inhumanly quick keystrokes in a row we take to mean a paste.
+const PASTE_BURST: usize = 4;
+
+/// Spots pasted text, which without bracketed paste support in the terminal
+/// library arrives as a burst of ordinary key events. Pasting makes a nonsense
+/// of the timings, so a test that's pasted into is ended and doesn't count.
+#[derive(Debug, Default)]
+struct PasteDetector {
+    burst: usize,
+}
+
+impl PasteDetector {
+    /// Feed in the time since the previous keystroke, returning whether the
+    /// keystrokes so far look like a paste.
+    fn keystroke(&mut self, interval: Duration) -> bool {
+        if interval < PASTE_GAP {
+            self.burst += 1;
+        } else {
+            self.burst = 0;
+        }
+
+        self.burst >= PASTE_BURST
+    }
+}
+
+/// A gap between keystrokes longer than this is taken as hesitating rather
+/// than typing.
+const IDLE_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Where a test's time went.
+#[derive(Debug, Default, PartialEq)]
+pub struct TimeBreakdown {
+    /// Time spent on keystrokes that came along at a typing pace.
+    pub typing: Duration,
+    /// Time spent waiting more than `IDLE_THRESHOLD` to press a key.
+    pub idle: Duration,
+    /// Time spent on backspaces.
+    pub correcting: Duration,
+}
+
+impl TimeBreakdown {
+    pub fn total(&self) -> Duration {
+        self.typing + self.idle + self.correcting
+    }
+}
+
+/// The version of the serialised `TestResults` format. Bump it whenever a
+/// change means older versions of dvors couldn't read the new files, or this
+/// one couldn't read the old ones: renaming or removing a field, or adding a
+/// `Metric` variant. Adding a field with a `#[serde(default)]` doesn't need a
+/// bump. Files saved before the version was recorded have the version 1 layout.
+///
+/// Version 2 added `Metric::Backspace`, so version 1 files are still readable.
+const RESULTS_VERSION: u32 = 2;
+
+/// The oldest version of the format this one can still read.
+const OLDEST_RESULTS_VERSION: u32 = 1;
+
+fn unversioned() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestResults<'a> {
+    #[serde(default = "unversioned")]
+    version: u32,
+    #[serde(borrow)]
+    words: Vec<FinishedWord<'a>>,
+    /// Whether text was pasted in, making the timings meaningless.
+    #[serde(default)]
+    pasted: bool,
+    /// What the test was taken with, which files saved before it was
+    /// recorded don't say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    settings: Option<Settings>,
+    /// Whether to leave the first keystroke out of the test's time, since
+    /// it's timed from the start of the test rather than a previous key.
+    #[serde(default)]
+    skip_first_keystroke: bool,
+    /// Whether the test was ended with Ctrl+C, to quit everything.
+    #[serde(skip)]
+    interrupted: bool,
+}
+
+impl<'a> From<Vec<FinishedWord<'a>>> for TestResults<'a> {
+    fn from(words: Vec<FinishedWord<'a>>) -> Self {
+        Self {
+            version: RESULTS_VERSION,
+            words,
+            pasted: false,
+            settings: None,
+            skip_first_keystroke: false,
+            interrupted: false,
+        }
+    }
+}
+
+/// Just enough of a serialised `TestResults` to check whether we can read the
+/// rest of it.
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default = "unversioned")]
+    version: u32,
+}
+
+pub type Trigram = (char, char, char);
+
+/// When each keystroke of a test was made, counting from the first, and how
+/// many characters into the text it left the typist.
+pub type Timeline = Vec<(Duration, usize)>;
+
+/// How many times each expected character was mistyped as each other
+/// character, keyed by `(expected, typed)`.
+pub type Confusions = HashMap<(char, char), usize>;
+
+/// Results made up of the results of several tests, one after the other.
+impl<'a> FromIterator<TestResults<'a>> for TestResults<'a> {
+    fn from_iter<I: IntoIterator<Item = TestResults<'a>>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(TestResults::from(vec![]), TestResults::merge)
+    }
+}
+
+impl<'a> TestResults<'a> {
+    /// Parse a session saved with `--export`, checking its version first so
+    /// that a file from a newer version of dvors gets a clear error rather
+    /// than whatever serde makes of it.
+    #[throws]
+    pub fn parse_session(json: &'a str) -> Vec<Self> {
+        let probes: Vec<VersionProbe> = serde_json::from_str(json)?;
+        if let Some(probe) = probes
+            .iter()
+            .find(|probe| !(OLDEST_RESULTS_VERSION..=RESULTS_VERSION).contains(&probe.version))
+        {
+            throw!(Error::VersionError {
+                found: probe.version,
+                supported: RESULTS_VERSION,
+            });
+        }
+
+        serde_json::from_str(json)?
+    }
+
+    /// Add the words of `other` on to the end of these, so that everything
+    /// is worked out over both tests. The results count as pasted if either
+    /// was, and only keep their settings if both were taken with the same.
+    pub fn merge(mut self, other: Self) -> Self {
+        if self.words.is_empty() {
+            return other;
+        }
+
+        self.words.extend(other.words);
+        self.pasted |= other.pasted;
+        self.interrupted |= other.interrupted;
+        if self.settings != other.settings {
+            self.settings = None;
+        }
+
+        self
+    }
+
+    /// The results with their own copies of the words, so they can outlive
+    /// the words the test was given.
+    pub fn into_owned(self) -> TestResults<'static> {
+        TestResults {
+            version: self.version,
+            words: self
+                .words
+                .into_iter()
+                .map(FinishedWord::into_owned)
+                .collect(),
+            pasted: self.pasted,
+            settings: self.settings,
+            skip_first_keystroke: self.skip_first_keystroke,
+            interrupted: self.interrupted,
+        }
+    }
+
+    pub fn words(&self) -> &[FinishedWord<'a>] {
+        &self.words
+    }
+
+    pub fn pasted(&self) -> bool {
+        self.pasted
+    }
+
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    pub fn settings(&self) -> Option<&Settings> {
+        self.settings.as_ref()
+    }
 
-impl TestResults<'_> {
     pub fn word_cnt(&self) -> usize {
-        self.0.len()
+        self.words.len()
     }
 
     pub fn normalised_word_durations(&self) -> Vec<u64> {
-        self.0
+        self.words
             .iter()
             .map(|word| {
-                let word_duration_seconds = word
-                    .metrics()
-                    .iter()
-                    .fold(Duration::default(), |acc, metric| match metric {
-                        Metric::Delimiter { duration, .. }
-                        | Metric::Match { duration, .. }
-                        | Metric::Typo { duration, .. } => acc + *duration,
-                    })
-                    .as_secs_f64();
+                let word_duration_seconds = word.duration().as_secs_f64();
 
                 ((word_duration_seconds / word.len_inc_delim() as f64) * 1000.0) as u64
             })
             .collect::<Vec<u64>>()
     }
 
+    /// How long the middle word took, in seconds, so one slow word can't
+    /// drag it out the way it would an average.
+    pub fn median_word_duration_secs(&self) -> f64 {
+        let mut secs = self
+            .words
+            .iter()
+            .map(|word| word.duration().as_secs_f64())
+            .collect::<Vec<f64>>();
+        secs.sort_by(f64::total_cmp);
+
+        let mid = secs.len() / 2;
+        match secs.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (secs[mid - 1] + secs[mid]) / 2.0,
+            _ => secs[mid],
+        }
+    }
+
+    /// How steady the rhythm was from one word to the next, from 0 to 100:
+    /// 100 less the coefficient of variation of the word times as a
+    /// percentage. With only one word there's nothing to vary, so it's 100.
+    pub fn consistency(&self) -> f64 {
+        let secs = self
+            .words
+            .iter()
+            .map(|word| word.duration().as_secs_f64())
+            .collect::<Vec<f64>>();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        if secs.len() < 2 || mean <= 0.0 {
+            return 100.0;
+        }
+
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+
+        (100.0 * (1.0 - variance.sqrt() / mean)).clamp(0.0, 100.0)
+    }
+
+    pub fn per_word_wpm(&self) -> Vec<u64> {
+        self.words.iter().map(word_wpm).collect::<Vec<u64>>()
+    }
+
+    /// Like `per_word_wpm`, but leaving out any word with a typo in it.
+    pub fn clean_word_wpm(&self) -> Vec<u64> {
+        self.words
+            .iter()
+            .filter(|word| !word.has_typo())
+            .map(word_wpm)
+            .collect::<Vec<u64>>()
+    }
+
+    /// How long each word took in milliseconds, lining up with `per_word_wpm`,
+    /// or with `clean_word_wpm` if `clean` is set.
+    pub fn word_millis(&self, clean: bool) -> Vec<u64> {
+        self.words
+            .iter()
+            .filter(|word| !clean || !word.has_typo())
+            .map(|word| word.duration().as_millis() as u64)
+            .collect()
+    }
+
+    /// The characters typed, not counting those of skipped words.
     pub fn char_cnt(&self) -> usize {
-        self.0
+        self.words
             .iter()
+            .filter(|word| !word.skipped())
             .fold(0, |acc, word| acc + word.len_inc_delim())
     }
 
+    /// How long the very first keystroke of the test took. There's no key
+    /// before it to time it from, so it includes however long it took to get
+    /// going after the test started, which can skew a short test.
+    pub fn first_keystroke(&self) -> Duration {
+        self.words
+            .iter()
+            .find_map(|word| word.metrics().first())
+            .map_or(Duration::ZERO, Metric::duration)
+    }
+
+    /// The time taken, leaving out the first keystroke's if the test was
+    /// taken with `--skip-first-keystroke`.
     pub fn duration_secs(&self) -> f64 {
-        let duration = self.0.iter().fold(Duration::default(), |acc, word| {
-            acc + word
-                .metrics()
-                .iter()
-                .fold(Duration::default(), |acc, metric| match metric {
-                    Metric::Delimiter { duration, .. }
-                    | Metric::Match { duration, .. }
-                    | Metric::Typo { duration, .. } => acc + *duration,
-                })
-        });
+        let duration = self
+            .words
+            .iter()
+            .fold(Duration::default(), |acc, word| acc + word.duration());
+
+        if self.skip_first_keystroke {
+            (duration - self.first_keystroke()).as_secs_f64()
+        } else {
+            duration.as_secs_f64()
+        }
+    }
+
+    /// How many words of five characters `char_cnt`, counted from the words
+    /// that weren't skipped, comes to per minute of the time spent on them.
+    /// With `--skip-first-keystroke` the first character is left out along
+    /// with its time.
+    fn per_minute(&self, mut char_cnt: f64) -> f64 {
+        let mut secs = self
+            .words
+            .iter()
+            .filter(|word| !word.skipped())
+            .map(FinishedWord::duration)
+            .sum::<Duration>()
+            .as_secs_f64();
+        // The first keystroke only comes out of what counted towards it.
+        if self.skip_first_keystroke && self.words.first().is_some_and(|word| !word.skipped()) {
+            char_cnt = (char_cnt - 1.0).max(0.0);
+            secs -= self.first_keystroke().as_secs_f64();
+        }
 
-        duration.as_secs_f64()
+        if secs > 0.0 {
+            char_cnt / 5.0 / (secs / 60.0)
+        } else {
+            0.0
+        }
     }
 
+    /// The average wpm. A skipped word's characters don't count towards it,
+    /// so nor does the time spent on it.
     pub fn wpm_avg(&self) -> f64 {
-        let word_cnt = self.char_cnt() as f64 / 5.0;
+        self.per_minute(self.char_cnt() as f64)
+    }
+
+    /// Every character typed in the words that weren't skipped, typos and
+    /// delimiters included.
+    fn typed_cnt(&self) -> usize {
+        self.words
+            .iter()
+            .filter(|word| !word.skipped())
+            .flat_map(|word| word.metrics())
+            .filter(|metric| {
+                matches!(
+                    metric,
+                    Metric::Match { .. } | Metric::Typo { .. } | Metric::Delimiter { .. }
+                )
+            })
+            .count()
+    }
+
+    /// The wpm counting every character typed, typos and all.
+    pub fn raw_wpm(&self) -> f64 {
+        self.per_minute(self.typed_cnt() as f64)
+    }
+
+    /// The raw wpm less a word for every typo that was typed on from rather
+    /// than taken straight back, the way most typing sites count an error
+    /// left standing. A word can't be finished until it's right, so this is
+    /// what's left of the errors by then. It never drops below zero.
+    pub fn net_wpm(&self) -> f64 {
+        let uncorrected = self
+            .words
+            .iter()
+            .filter(|word| !word.skipped())
+            .flat_map(|word| word.metrics().windows(2))
+            .filter(|pair| {
+                matches!(pair[0], Metric::Typo { .. })
+                    && !matches!(pair[1], Metric::Backspace { .. })
+            })
+            .count();
+        let net = self.typed_cnt() as f64 - uncorrected as f64 * 5.0;
+
+        self.per_minute(net.max(0.0))
+    }
+
+    /// The percentage of keystrokes within words that were right first time.
+    /// Delimiters don't count either way, and a test with nothing typed in it
+    /// has nothing wrong with it, so is 100% accurate.
+    pub fn accuracy(&self) -> f64 {
+        accuracy(self.words.iter().flat_map(|word| word.metrics())).unwrap_or(100.0)
+    }
+
+    /// The percentage of keystrokes that were right first time where what
+    /// should have been typed takes shift with the `number_row`, or `None` if
+    /// nothing did.
+    pub fn shifted_accuracy(&self, number_row: NumberRow) -> Option<f64> {
+        accuracy(
+            self.words
+                .iter()
+                .flat_map(|word| word.metrics())
+                .filter(|metric| match metric {
+                    Metric::Match { value: c, .. } | Metric::Typo { expected: c, .. } => {
+                        number_row.is_shifted(*c)
+                    }
+                    _ => false,
+                }),
+        )
+    }
+
+    /// Split the time taken into typing, hesitating and correcting. A
+    /// backspace counts as correcting however long it took, and any other key
+    /// pressed after more than `IDLE_THRESHOLD` as hesitating.
+    pub fn time_breakdown(&self) -> TimeBreakdown {
+        let mut breakdown = TimeBreakdown::default();
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            let duration = metric.duration();
+            match metric {
+                Metric::Backspace { .. } => breakdown.correcting += duration,
+                _ if duration > IDLE_THRESHOLD => breakdown.idle += duration,
+                _ => breakdown.typing += duration,
+            }
+        }
+
+        breakdown
+    }
+
+    /// Whether the test passes `--mastery`: every word typed, none of them
+    /// skipped, without a single typo and at `target_wpm` or faster.
+    pub fn mastered(&self, target_wpm: f64) -> bool {
+        let finished = match &self.settings {
+            Some(settings) => self.word_cnt() >= settings.words,
+            None => self.word_cnt() > 0,
+        };
+
+        finished
+            && !self.pasted
+            && !self.words.iter().any(FinishedWord::skipped)
+            && self.typo_cnt() == 0
+            && self.wpm_avg() >= target_wpm
+    }
+
+    /// How erratic the timing of correct keystrokes was: the standard
+    /// deviation of their durations as a proportion of the mean. Lower is
+    /// smoother, and it's 0 with fewer than two keystrokes to compare.
+    pub fn rhythm_jitter(&self) -> f64 {
+        let intervals = self
+            .words
+            .iter()
+            .flat_map(|word| word.metrics())
+            .filter_map(|metric| match metric {
+                Metric::Match { duration, .. } => Some(duration.as_secs_f64()),
+                _ => None,
+            })
+            .collect::<Vec<f64>>();
+        if intervals.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+        let variance = intervals
+            .iter()
+            .map(|interval| (interval - mean).powi(2))
+            .sum::<f64>()
+            / intervals.len() as f64;
 
-        if !self.0.is_empty() {
-            word_cnt / (self.duration_secs() / 60.0)
+        if mean > 0.0 {
+            variance.sqrt() / mean
         } else {
             0.0
         }
     }
 
-    pub fn typo_cnt(&self) -> usize {
-        self.0.iter().fold(0, |acc, word| {
-            acc + word.metrics().iter().fold(0, |acc, metric| {
-                if let Metric::Typo { .. } = metric {
-                    acc + 1
-                } else {
-                    acc
+    /// The percentage of keystrokes that landed on each of the keyboard's
+    /// rows, from the number row down. Keystrokes off the rows, like spaces,
+    /// aren't counted.
+    pub fn row_distribution(&self) -> [f64; 4] {
+        let mut cnts = [0; 4];
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            if let Metric::Match { value, .. } | Metric::Typo { value, .. } = metric {
+                if let Some(row) = heatmap::row(*value) {
+                    cnts[row] += 1;
+                }
+            }
+        }
+
+        let total = cnts.iter().sum::<usize>();
+        if total == 0 {
+            return [0.0; 4];
+        }
+        cnts.map(|cnt| cnt as f64 / total as f64 * 100.0)
+    }
+
+    /// The `Timeline` of the test, for racing it again. Words are counted as
+    /// the length they should have been, so a word skipped partway through
+    /// doesn't throw out where the next one starts.
+    pub fn timeline(&self) -> Timeline {
+        let mut timeline = vec![];
+        let mut elapsed = Duration::ZERO;
+        let mut word_start = 0;
+        for word in &self.words {
+            let mut position = 0_usize;
+            for metric in word.metrics() {
+                elapsed += metric.duration();
+                match metric {
+                    Metric::Backspace { .. } => position = position.saturating_sub(1),
+                    _ => position += 1,
                 }
+                timeline.push((elapsed, word_start + position));
+            }
+            word_start += word.len_inc_delim();
+        }
+
+        // The ghost sets off with the first keystroke, not the start of the
+        // test.
+        if let Some(&(first, _)) = timeline.first() {
+            for (time, _) in &mut timeline {
+                *time -= first;
+            }
+        }
+
+        timeline
+    }
+
+    /// Roughly how far the fingers moved, in key widths. Each finger sets off
+    /// from its home key and moves straight from key to key as it's used,
+    /// staying wherever it last pressed.
+    pub fn finger_travel(&self) -> f64 {
+        let mut positions = hands::FINGERS.map(|finger| heatmap::position(finger.home()));
+        let mut travel = 0.0;
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            if let Metric::Match { value, .. } | Metric::Typo { value, .. } = metric {
+                if let (Some(finger), Some((x, y))) =
+                    (hands::finger(*value), heatmap::position(*value))
+                {
+                    if let Some((last_x, last_y)) = positions[finger as usize].replace((x, y)) {
+                        travel += (x - last_x).hypot(y - last_y);
+                    }
+                }
+            }
+        }
+
+        travel
+    }
+
+    /// Correctly typed characters (delimiters included) per second.
+    pub fn cps(&self) -> f64 {
+        let correct = self
+            .words
+            .iter()
+            .flat_map(|word| word.metrics())
+            .filter(|metric| matches!(metric, Metric::Match { .. } | Metric::Delimiter { .. }))
+            .count();
+        let secs = self.duration_secs();
+
+        if secs > 0.0 {
+            correct as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The average time taken to start typing each word, i.e. the gap between
+    /// the previous word's delimiter and the word's first keystroke.
+    pub fn avg_word_initiation(&self) -> Duration {
+        let initiations = self
+            .words
+            .iter()
+            .filter_map(|word| {
+                word.metrics().iter().find_map(|metric| match metric {
+                    Metric::Match { duration, .. } | Metric::Typo { duration, .. } => {
+                        Some(*duration)
+                    }
+                    _ => None,
+                })
             })
-        })
+            .collect::<Vec<Duration>>();
+
+        if !initiations.is_empty() {
+            initiations.iter().sum::<Duration>() / initiations.len() as u32
+        } else {
+            Duration::default()
+        }
     }
-}
 
-#[throws]
-pub(crate) fn typing_test<'a, 'b, B: tui::backend::Backend>(
-    terminal: &'b mut Terminal<B>,
-    mut test_words: VecDeque<&'a str>,
-) -> TestResults<'a> {
-    let mut test_word = Word::from(test_words.pop_front().unwrap());
-    let mut typed = String::new();
-    let mut finished_words = vec![];
+    /// The average time taken to type the third character of each sequence of
+    /// three correct keystrokes. Word boundaries count as a space, and a typo
+    /// breaks the sequence.
+    pub fn trigram_latencies(&self) -> HashMap<Trigram, Duration> {
+        let mut totals: HashMap<Trigram, (Duration, u32)> = HashMap::new();
+        let mut window: VecDeque<char> = VecDeque::with_capacity(3);
 
-    let mut start_char = SystemTime::now();
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            let (value, duration) = match metric {
+                Metric::Match { value, duration } => (*value, *duration),
+                Metric::Delimiter { duration, .. } => (' ', *duration),
+                Metric::Typo { .. } | Metric::Backspace { .. } => {
+                    window.clear();
+                    continue;
+                }
+            };
 
-    loop {
-        terminal.draw(|mut frame| {
-            let size = frame.size();
+            if window.len() == 3 {
+                window.pop_front();
+            }
+            window.push_back(value);
 
-            let remaining_words = test_words
-                .iter()
-                .map(|s| format!(" {}", s))
-                .collect::<String>()
-                .chars()
-                .skip(test_word.overflow())
-                .collect::<String>();
+            if let [a, b, c] = window.iter().copied().collect::<Vec<char>>()[..] {
+                let total = totals.entry((a, b, c)).or_default();
+                total.0 += duration;
+                total.1 += 1;
+            }
+        }
 
-            let mut text = vec![Text::styled(&typed, Style::default().fg(Color::DarkGray))];
-            text.extend_from_slice(&test_word.styled_text());
-            text.push(Text::styled(
-                remaining_words,
-                Style::default().fg(Color::DarkGray),
-            ));
+        totals
+            .into_iter()
+            .map(|(trigram, (duration, cnt))| (trigram, duration / cnt))
+            .collect()
+    }
 
-            let block = Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray));
-            let paragraph = Paragraph::new(text.iter()).block(block).wrap(true);
-            frame.render_widget(paragraph, size);
-        })?;
+    /// The average keystroke latency at each position within a word: the first
+    /// letter typed, the second, and so on. Positions count keystrokes, so a
+    /// corrected typo pushes the rest of the word along.
+    pub fn latency_by_position(&self) -> Vec<Duration> {
+        let mut totals: Vec<(Duration, u32)> = vec![];
+        for word in &self.words {
+            let keystrokes = word.metrics().iter().filter_map(|metric| match metric {
+                Metric::Match { duration, .. } | Metric::Typo { duration, .. } => Some(duration),
+                _ => None,
+            });
+            for (idx, duration) in keystrokes.enumerate() {
+                if totals.len() <= idx {
+                    totals.push((Duration::default(), 0));
+                }
+                totals[idx].0 += *duration;
+                totals[idx].1 += 1;
+            }
+        }
 
-        if let Event::Key(event) = read()? {
-            if event.code == KeyCode::Esc {
+        totals.into_iter().map(|(total, cnt)| total / cnt).collect()
+    }
+
+    /// The `n` trigrams with the highest average latency, slowest first.
+    pub fn slowest_trigrams(&self, n: usize) -> Vec<(Trigram, Duration)> {
+        let mut trigrams = self.trigram_latencies().into_iter().collect::<Vec<_>>();
+        trigrams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        trigrams.truncate(n);
+
+        trigrams
+    }
+
+    pub fn typo_confusions(&self) -> Confusions {
+        let mut confusions = Confusions::new();
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            if let Metric::Typo {
+                value, expected, ..
+            } = metric
+            {
+                *confusions.entry((*expected, *value)).or_insert(0) += 1;
+            }
+        }
+
+        confusions
+    }
+
+    /// How many typos were made where each key should have been pressed,
+    /// most first, going by the character that was wanted rather than the one
+    /// hit instead.
+    pub fn typo_counts_by_expected(&self) -> Vec<(char, usize)> {
+        let mut counts = HashMap::new();
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            if let Metric::Typo { expected, .. } = metric {
+                *counts.entry(*expected).or_insert(0) += 1;
+            }
+        }
+
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        counts
+    }
+
+    /// How many keystrokes were made with the left and right hands.
+    pub fn hand_balance(&self) -> (usize, usize) {
+        self.words
+            .iter()
+            .flat_map(|word| word.metrics())
+            .filter_map(|metric| match metric {
+                Metric::Match { value, .. } | Metric::Typo { value, .. } => hands::hand(*value),
+                _ => None,
+            })
+            .fold((0, 0), |(left, right), hand| match hand {
+                Hand::Left => (left + 1, right),
+                Hand::Right => (left, right + 1),
+            })
+    }
+
+    /// The most words in a row typed without a single typo.
+    pub fn longest_clean_word_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut streak = 0;
+        for word in &self.words {
+            if word.has_typo() {
+                streak = 0;
+            } else {
+                streak += 1;
+                longest = longest.max(streak);
+            }
+        }
+
+        longest
+    }
+
+    /// The `n` different words that went worst: those with the most typos
+    /// first, then the slowest.
+    pub fn worst_words(&self, n: usize) -> Vec<&str> {
+        let mut scored = self
+            .words
+            .iter()
+            .map(|word| {
+                let typos = word
+                    .metrics()
+                    .iter()
+                    .filter(|metric| matches!(metric, Metric::Typo { .. }))
+                    .count();
+                (
+                    word.value(),
+                    typos + usize::from(word.skipped()),
+                    word_wpm(word),
+                )
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+
+        let mut worst: Vec<&str> = vec![];
+        for (value, _, _) in scored {
+            if worst.len() == n {
                 break;
             }
-            let c = keymap::qwerty_to_dvorak(event.code);
-            match c {
-                KeyCode::Backspace => test_word.remove_char(),
-                KeyCode::Char(c) => {
-                    if c == ' ' && test_word.is_complete() {
-                        typed.push_str(test_word.as_str());
-                        typed.push(' ');
+            if !worst.contains(&value) {
+                worst.push(value);
+            }
+        }
 
-                        let finished_word = test_word.finalise(c, start_char.elapsed()?);
-                        finished_words.push(finished_word);
+        worst
+    }
 
-                        test_word = match test_words.pop_front() {
-                            Some(word) => {
-                                start_char = SystemTime::now();
-                                word.into()
-                            }
-                            None => break,
-                        };
-                    } else {
-                        test_word.add_char(c, start_char.elapsed()?);
-                        start_char = SystemTime::now();
+    /// How often each key was expected, and how often it was mistyped.
+    pub fn key_stats(&self) -> KeyStats {
+        let mut stats = KeyStats::new();
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            let (expected, typo) = match metric {
+                Metric::Match { value, .. } => (*value, false),
+                Metric::Typo { expected, .. } => (*expected, true),
+                _ => continue,
+            };
+            let entry = stats.entry(heatmap::unshifted(expected)).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 += usize::from(typo);
+        }
+
+        stats
+    }
+
+    /// The accuracy and average latency of the second press of each doubled
+    /// letter (like the second l in "hello"), or `None` if there weren't any.
+    pub fn double_letter_stats(&self) -> Option<(f64, Duration)> {
+        let mut presses = vec![];
+        for word in &self.words {
+            let expected = word.value().chars().collect::<Vec<char>>();
+            // Backspaces move back to retype a position, so follow where in
+            // the word each keystroke lands.
+            let mut position = 0;
+            for metric in word.metrics() {
+                match metric {
+                    Metric::Match { duration, .. } | Metric::Typo { duration, .. } => {
+                        if position > 0
+                            && position < expected.len()
+                            && expected[position] == expected[position - 1]
+                        {
+                            presses.push((metric, *duration));
+                        }
+                        position += 1;
                     }
+                    Metric::Backspace { .. } => position = position.saturating_sub(1),
+                    _ => {}
+                }
+            }
+        }
+
+        if presses.is_empty() {
+            return None;
+        }
+        let accuracy = accuracy(presses.iter().map(|(metric, _)| *metric))?;
+        let latency = presses
+            .iter()
+            .map(|(_, duration)| *duration)
+            .sum::<Duration>()
+            / presses.len() as u32;
+
+        Some((accuracy, latency))
+    }
+
+    /// The lengths of the runs of typos in a row, in order, carrying on across
+    /// word boundaries. Backspacing over a typo doesn't end the run, only
+    /// typing something right does.
+    pub fn typo_clusters(&self) -> Vec<usize> {
+        let mut clusters = vec![];
+        let mut run = 0;
+        for metric in self.words.iter().flat_map(|word| word.metrics()) {
+            match metric {
+                Metric::Typo { .. } => run += 1,
+                Metric::Backspace { .. } => {}
+                _ if run > 0 => {
+                    clusters.push(run);
+                    run = 0;
                 }
                 _ => {}
             }
         }
+        if run > 0 {
+            clusters.push(run);
+        }
+
+        clusters
+    }
+
+    pub fn max_typo_streak(&self) -> usize {
+        self.typo_clusters().into_iter().max().unwrap_or(0)
     }
 
-    TestResults(finished_words)
+    /// How many words were left shorter than they should be, and how many had
+    /// characters typed past their end.
+    pub fn length_errors(&self) -> (usize, usize) {
+        let dropped = self.words.iter().filter(|word| word.dropped()).count();
+        let extra = self.words.iter().filter(|word| word.overflowed()).count();
+
+        (dropped, extra)
+    }
+
+    /// Every key pressed: each character and backspace, each delimiter, and
+    /// the Esc that skipped any skipped words. Keys typed past the end of a
+    /// word aren't recorded, so can't be counted.
+    pub fn total_keystrokes(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.metrics().len() + usize::from(word.skipped()))
+            .sum()
+    }
+
+    pub fn typo_cnt(&self) -> usize {
+        self.words.iter().fold(0, |acc, word| {
+            acc + word.metrics().iter().fold(0, |acc, metric| {
+                if let Metric::Typo { .. } = metric {
+                    acc + 1
+                } else {
+                    acc
+                }
+            })
+        })
+    }
+}
+
+/// A rough idea of how long typing `test_words` will take at `wpm`.
+fn estimate_duration(test_words: &VecDeque<&str>, wpm: f64) -> Duration {
+    let chars = test_words
+        .iter()
+        .map(|word| word.chars().count() + 1)
+        .sum::<usize>();
+
+    Duration::from_secs_f64(chars as f64 / 5.0 / wpm * 60.0)
+}
+
+/// Describe the test about to be taken, with an estimate of how long it'll
+/// take if there's a speed to go on.
+fn splash_text(test_words: &VecDeque<&str>, wpm: Option<f64>) -> String {
+    let mut text = format!("{} words", test_words.len());
+    if let Some(wpm) = wpm.filter(|wpm| *wpm > 0.0) {
+        let secs = estimate_duration(test_words, wpm).as_secs();
+        let eta = if secs < 60 {
+            format!("~{} s", secs)
+        } else {
+            format!("~{} min", (secs as f64 / 60.0).round())
+        };
+        text.push_str(&format!(" · {} at your {:.0} wpm", eta, wpm));
+    }
+
+    text
+}
+
+/// Show what's coming up before a test starts, returning `false` if Esc is
+/// pressed to quit instead. Any other key starts the test.
+#[throws]
+pub(crate) fn show_splash<B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    theme: &Theme,
+    test_words: &VecDeque<&str>,
+    wpm: Option<f64>,
+    config: &Config,
+    notice: Option<&str>,
+) -> bool {
+    let mut text = vec![Text::raw(splash_text(test_words, wpm))];
+    if let Some(notice) = notice {
+        text.push(Text::styled(
+            format!("\n{}", notice),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    text.push(Text::styled(
+        "\n\npress any key to start, ? for where to rest your fingers, or Esc to quit",
+        theme.preview,
+    ));
+
+    loop {
+        terminal.draw(|mut frame| {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(theme.border);
+            let paragraph = Paragraph::new(text.iter()).block(block);
+            frame.render_widget(paragraph, frame.size());
+        })?;
+
+        if let Event::Key(event) = read()? {
+            if keymap::is_interrupt(&event) {
+                break false;
+            }
+            if event.code == KeyCode::Char('?') {
+                keyboard::show_home_keys(terminal, theme, config.number_row, config.mirror)?;
+                continue;
+            }
+            break event.code != KeyCode::Esc;
+        }
+    }
+}
+
+/// The percentage of `metrics` that are matches rather than typos, if there
+/// are any of either.
+fn accuracy<'m>(metrics: impl Iterator<Item = &'m Metric>) -> Option<f64> {
+    let (matches, typos) = metrics.fold((0, 0), |(matches, typos), metric| match metric {
+        Metric::Match { .. } => (matches + 1, typos),
+        Metric::Typo { .. } => (matches, typos + 1),
+        _ => (matches, typos),
+    });
+
+    if matches + typos > 0 {
+        Some(matches as f64 / (matches + typos) as f64 * 100.0)
+    } else {
+        None
+    }
+}
+
+/// Lowercase `c` unless what's `expected` is an uppercase letter, so that a
+/// stray shift or caps lock doesn't make a typo.
+fn fold_case(c: char, expected: Option<char>) -> char {
+    if expected.is_some_and(char::is_uppercase) {
+        c
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// How far ahead of a replayed ghost `lead` characters is, coloured to say
+/// whether that's good news.
+fn lead_text(lead: i64) -> (String, Color) {
+    match lead {
+        0 => (" · level".to_string(), Color::Yellow),
+        lead if lead > 0 => (format!(" · ahead by {}", lead), Color::Green),
+        lead => (format!(" · behind by {}", -lead), Color::Red),
+    }
+}
+
+/// How far into the text the ghost of a `timeline` had got `elapsed` after
+/// its first keystroke.
+fn replay_position(timeline: &Timeline, elapsed: Duration) -> usize {
+    timeline
+        .iter()
+        .take_while(|(time, _)| *time <= elapsed)
+        .last()
+        .map_or(0, |(_, position)| *position)
+}
+
+/// The time left in a timed test, rounded up so it only reads 0s once it's
+/// over.
+fn time_left_text(time_left: Duration) -> String {
+    let secs = time_left.as_secs() + u64::from(time_left.subsec_nanos() > 0);
+
+    format!(" · time left {}s", secs)
+}
+
+/// The running wpm of `char_cnt` characters typed in `elapsed`, which is 0
+/// until there's something to go on.
+fn live_wpm_text(char_cnt: usize, elapsed: Duration) -> String {
+    let minutes = elapsed.as_secs_f64() / 60.0;
+    let wpm = if char_cnt > 0 && minutes > 0.0 {
+        char_cnt as f64 / 5.0 / minutes
+    } else {
+        0.0
+    };
+
+    format!("{:.0} wpm", wpm)
+}
+
+/// Which finger and row type `c`, for beginners still learning where the keys
+/// are.
+fn hint_text(c: char) -> String {
+    let mut hint = format!(" next: {:?}", c);
+    if let (Some(finger), Some(row)) = (hands::finger(c), heatmap::row_name(c)) {
+        hint.push_str(&format!(" — {}, {}", finger.name(), row));
+    }
+
+    hint
+}
+
+fn word_wpm(word: &FinishedWord) -> u64 {
+    let word_duration_minutes = word.duration().as_secs_f64() / 60.0;
+
+    if word_duration_minutes > 0.0 && !word.skipped() {
+        ((word.len_inc_delim() as f64 / 5.0) / word_duration_minutes) as u64
+    } else {
+        0
+    }
+}
+
+/// Take the next word off the front of `test_words`, topping them up with
+/// `refill` once they're all gone so that there's always a preview.
+fn next_word<'a>(
+    test_words: &mut VecDeque<&'a str>,
+    refill: &mut dyn FnMut() -> VecDeque<&'a str>,
+) -> Option<&'a str> {
+    let word = test_words.pop_front();
+    if test_words.is_empty() {
+        test_words.extend(refill());
+    }
+
+    word
+}
+
+/// Run a test of `test_words`, ending as `mode` says. A timed test calls
+/// `refill`, if there is one, for more words whenever they run out.
+#[throws]
+pub(crate) fn typing_test<'a, B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    config: &'a Config,
+    theme: &Theme,
+    caption: Option<&str>,
+    test_words: VecDeque<&'a str>,
+    mode: TestMode,
+    refill: Option<&mut dyn FnMut() -> VecDeque<&'a str>>,
+) -> TestResults<'a> {
+    let refill = refill.filter(|_| matches!(mode, TestMode::Timed(_)));
+
+    run_test(
+        terminal, config, theme, caption, test_words, mode, refill, None,
+    )?
+}
+
+/// Like `typing_test`, but calling `refill` for more words whenever they run
+/// out whatever the `mode`, so that the test only ends with Esc (or the time
+/// limit).
+#[throws]
+pub(crate) fn endless_typing_test<'a, B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    config: &'a Config,
+    theme: &Theme,
+    caption: Option<&str>,
+    test_words: VecDeque<&'a str>,
+    mode: TestMode,
+    refill: &mut dyn FnMut() -> VecDeque<&'a str>,
+) -> TestResults<'a> {
+    run_test(
+        terminal,
+        config,
+        theme,
+        caption,
+        test_words,
+        mode,
+        Some(refill),
+        None,
+    )?
+}
+
+/// Like `typing_test`, but racing the ghost of a past run of the same words
+/// following its `timeline`, rather than one typing at a steady `--pace`.
+#[throws]
+pub(crate) fn race_typing_test<'a, B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    config: &'a Config,
+    theme: &Theme,
+    caption: Option<&str>,
+    test_words: VecDeque<&'a str>,
+    mode: TestMode,
+    timeline: &Timeline,
+) -> TestResults<'a> {
+    run_test(
+        terminal,
+        config,
+        theme,
+        caption,
+        test_words,
+        mode,
+        None,
+        Some(timeline),
+    )?
+}
+
+#[allow(clippy::too_many_arguments)]
+#[throws]
+fn run_test<'a, B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    config: &'a Config,
+    theme: &Theme,
+    caption: Option<&str>,
+    mut test_words: VecDeque<&'a str>,
+    mode: TestMode,
+    refill: Option<&mut dyn FnMut() -> VecDeque<&'a str>>,
+    replay: Option<&Timeline>,
+) -> TestResults<'a> {
+    let mut no_refill = VecDeque::new;
+    let refill = refill.unwrap_or(&mut no_refill);
+    let theme = &if config.focus {
+        theme.focused()
+    } else {
+        *theme
+    };
+    let options = RenderOptions {
+        blank: config.blank,
+        slow_after: config.slow_after(),
+    };
+    // Warmup words go first, and are left out of the results once typed.
+    let mut warmup = 0;
+    if let Some(words) = &config.warmup {
+        for word in words.split_whitespace().rev() {
+            test_words.push_front(word);
+            warmup += 1;
+        }
+    }
+    let (first_word, mut word_delimiter) = split_delimiter(
+        next_word(&mut test_words, refill).unwrap(),
+        config.delimiter.as_char(),
+    );
+    let mut test_word = Word::from(first_word);
+    let mut typed = String::new();
+    let mut typed_chars = 0;
+    // How much of that was warmup, which the live stats and the ghost leave out.
+    let mut warmup_chars = 0;
+    // Where the typed text still on screen starts. Lines that have scrolled
+    // off the top are left out, so that laying the text out doesn't slow down
+    // the longer the test goes on.
+    let mut shown_from = 0;
+    let mut finished_words = vec![];
+
+    let mut start_char = SystemTime::now();
+    let mut start_test: Option<SystemTime> = None;
+    // How long has been spent waiting on the next key since the last one,
+    // leaving out the redraws in between so that a slow terminal doesn't make
+    // a paste look like typing.
+    let mut waited: Option<Duration> = None;
+    let mut paste_detector = PasteDetector::default();
+    let mut pasted = false;
+    let mut interrupted = false;
+    let shown_at = SystemTime::now();
+    let mut composer = Composer::default();
+    let mut last_esc: Option<SystemTime> = None;
+    let mut keyboard = Keyboard::new(config.number_row, config.mirror);
+    // What was last drawn that changes without a key being pressed, so that
+    // the screen is only redrawn when something on it has moved on.
+    let mut drawn = None;
+    let mut redraw = true;
+
+    'test: loop {
+        // The ghost sets off with the first keystroke after the warmup, at the
+        // configured pace.
+        let ghost = match (replay, config.pace, start_test) {
+            (Some(timeline), _, Some(start)) => Some(replay_position(timeline, start.elapsed()?)),
+            (None, Some(wpm), Some(start)) => {
+                let minutes = start.elapsed()?.as_secs_f64() / 60.0;
+                Some((minutes * wpm as f64 * 5.0) as usize)
+            }
+            _ => None,
+        }
+        .map(|ghost| warmup_chars + ghost);
+        // How far ahead of a replayed ghost we are, or behind if negative.
+        let lead = ghost
+            .filter(|_| replay.is_some())
+            .map(|ghost| (typed_chars + test_word.typed_len()) as i64 - ghost as i64);
+
+        // The clock starts with the first keystroke, like the ghost's.
+        let time_left = match (mode, start_test) {
+            (TestMode::Timed(limit), Some(start)) => {
+                let time_left = limit.saturating_sub(start.elapsed()?);
+                if time_left == Duration::ZERO {
+                    break 'test;
+                }
+                Some(time_left)
+            }
+            (TestMode::Timed(limit), None) => Some(limit),
+            (TestMode::WordCount(_), _) => None,
+        };
+
+        // With the preview hidden only the word being typed is shown, so there's
+        // nothing to read ahead to (or back over).
+        let no_words = VecDeque::new();
+        let (shown_typed, shown_words) = if config.hide_preview {
+            ("", &no_words)
+        } else {
+            (&typed[shown_from..], &test_words)
+        };
+        let hidden = typed_chars - shown_typed.chars().count();
+        let ghost = ghost.and_then(|idx| idx.checked_sub(hidden));
+
+        let blink_phase = shown_at.elapsed()?.as_millis() / BLINK.as_millis();
+        let show_cursor = !config.blink_cursor || blink_phase.is_multiple_of(2);
+
+        let elapsed = match start_test {
+            Some(start) => start.elapsed()?,
+            None => Duration::ZERO,
+        };
+        let live_wpm = live_wpm_text(
+            (typed_chars + test_word.typed_len()).saturating_sub(warmup_chars),
+            elapsed,
+        );
+        let title = match caption {
+            Some(caption) => format!("{} · {}", caption, live_wpm),
+            None => live_wpm,
+        };
+
+        let shown = (
+            title.clone(),
+            time_left.map(time_left_text),
+            ghost,
+            lead,
+            show_cursor,
+        );
+        if redraw || drawn.as_ref() != Some(&shown) {
+            let live_accuracy = accuracy(
+                finished_words
+                    .iter()
+                    .flat_map(FinishedWord::metrics)
+                    .chain(test_word.metrics().iter().filter(|_| warmup == 0)),
+            );
+
+            terminal.draw(|mut frame| {
+                if !widgets::fits(&mut frame, MIN_WIDTH, MIN_HEIGHT) {
+                    return;
+                }
+                let hint_height = if config.hints { 1 } else { 0 };
+                let keyboard_height = if config.keyboard { KEYBOARD_HEIGHT } else { 0 };
+                let areas = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Min(0),
+                            Constraint::Length(1),
+                            Constraint::Length(hint_height),
+                            Constraint::Length(keyboard_height),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(frame.size());
+                let size = areas[0];
+
+                let block = Block::default()
+                    .title(&title)
+                    .borders(Borders::ALL)
+                    .border_style(theme.border);
+
+                let inline_cursor = shown_typed.chars().count() + test_word.typed_len();
+                let delimiter = config.delimiter.as_char();
+                let (text, scroll, cursor) = if config.stacked {
+                    let (text, cursor) = stacked_text(&test_word, shown_words, theme, options);
+
+                    (text, 0, cursor)
+                } else if config.wrap {
+                    let remaining_words =
+                        preview_text(shown_words, delimiter, word_delimiter, test_word.overflow());
+
+                    let mut text = vec![Text::styled(shown_typed, theme.typed)];
+                    text.extend_from_slice(&test_word.styled_text(theme, options));
+                    text.push(Text::styled(remaining_words, theme.preview));
+
+                    (text, 0, inline_cursor)
+                } else {
+                    let inner = block.inner(size);
+                    let (text, current_line, line_starts) = pinned_text(
+                        shown_typed,
+                        &test_word,
+                        shown_words,
+                        theme,
+                        options,
+                        delimiter,
+                        word_delimiter,
+                        inner.width,
+                    );
+                    let scroll = current_line.saturating_sub(usize::from(inner.height) / 2);
+                    // The lines above the top one shown won't be seen again.
+                    if let Some(start) = line_starts.get(scroll) {
+                        shown_from += start;
+                    }
+
+                    (text, scroll, inline_cursor)
+                };
+
+                let text = if show_cursor {
+                    modify_char(text, cursor, theme.cursor)
+                } else {
+                    text
+                };
+                // The ghost's position only makes sense when the text runs inline.
+                let text = match ghost.filter(|_| !config.stacked) {
+                    Some(idx) => modify_char(text, idx, theme.ghost),
+                    None => text,
+                };
+
+                let paragraph = Paragraph::new(text.iter())
+                    .block(block)
+                    .wrap(config.wrap)
+                    .scroll(scroll as u16);
+                frame.render_widget(paragraph, size);
+
+                let stats = match live_accuracy {
+                    Some(accuracy) => {
+                        let color = if accuracy >= config.target_accuracy {
+                            Color::Green
+                        } else {
+                            Color::Red
+                        };
+                        Text::styled(
+                            format!(" accuracy {:.0}%", accuracy),
+                            Style::default().fg(color),
+                        )
+                    }
+                    None => Text::styled(" accuracy --", theme.preview),
+                };
+                let mut stats = vec![stats];
+                if let Some(time_left) = time_left {
+                    let style = if time_left <= FINAL_SECONDS {
+                        Style::default().fg(Color::Red)
+                    } else {
+                        theme.preview
+                    };
+                    stats.push(Text::styled(time_left_text(time_left), style));
+                }
+                if let Some(lead) = lead {
+                    let (text, color) = lead_text(lead);
+                    stats.push(Text::styled(text, Style::default().fg(color)));
+                }
+                frame.render_widget(Paragraph::new(stats.iter()), areas[1]);
+
+                // Once the word's typed all that's left is the delimiter.
+                if let Some(next) = test_word.next_expected().filter(|_| config.hints) {
+                    let hint = Text::styled(hint_text(next), theme.preview);
+                    frame.render_widget(Paragraph::new([hint].iter()), areas[2]);
+                }
+
+                if config.keyboard {
+                    let key = test_word
+                        .next_expected()
+                        .and_then(|c| keyboard::key_code_to_key(KeyCode::Char(c)));
+                    keyboard.key_next(key);
+                    let block = Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(theme.border);
+                    let text = keyboard.styled_text();
+                    frame.render_widget(Paragraph::new(text.iter()).block(block), areas[3]);
+                }
+            })?;
+            drawn = Some(shown);
+            redraw = false;
+        }
+
+        let polled_at = SystemTime::now();
+        let ready = poll(TICK)?;
+        if let Some(waited) = &mut waited {
+            *waited += polled_at.elapsed()?;
+        }
+        if !ready {
+            continue;
+        }
+
+        // Whatever happened, a key or the terminal being resized, needs
+        // showing.
+        redraw = true;
+        if let Event::Key(event) = read()? {
+            if keymap::is_interrupt(&event) {
+                interrupted = true;
+                break;
+            }
+            if event.code == KeyCode::Esc {
+                // Without a double tap window every Esc quits.
+                let window = match config.double_esc {
+                    Some(ms) => Duration::from_millis(ms),
+                    None => break,
+                };
+                if let Some(last) = last_esc.replace(SystemTime::now()) {
+                    if last.elapsed()? < window {
+                        break;
+                    }
+                }
+
+                typed.push_str(test_word.as_str());
+                typed.push(word_delimiter);
+                typed_chars += test_word.as_str().chars().count() + 1;
+                let finished_word = mem::replace(&mut test_word, Word::from("")).skip();
+                if warmup > 0 {
+                    warmup -= 1;
+                    warmup_chars = typed_chars;
+                } else {
+                    finished_words.push(finished_word);
+                }
+                composer.cancel();
+
+                test_word = match next_word(&mut test_words, refill) {
+                    Some(word) => {
+                        start_char = SystemTime::now();
+                        let (word, delimiter) = split_delimiter(word, config.delimiter.as_char());
+                        word_delimiter = delimiter;
+                        word.into()
+                    }
+                    None => break,
+                };
+                continue;
+            }
+            if warmup == 0 {
+                start_test.get_or_insert_with(SystemTime::now);
+            }
+            if let Some(interval) = waited.replace(Duration::ZERO) {
+                if paste_detector.keystroke(interval) {
+                    pasted = true;
+                    break;
+                }
+            }
+            let c = keymap::qwerty_to_dvorak(event.code);
+            let chars = match c {
+                KeyCode::Backspace => {
+                    if !composer.cancel() && test_word.typed_len() > 0 {
+                        test_word.remove_char(start_char.elapsed()?);
+                        start_char = SystemTime::now();
+                    }
+                    vec![]
+                }
+                KeyCode::Char(c) => {
+                    let c = config.number_row.remap(c);
+                    let c = config.mirror.map_or(c, |half| keymap::mirror(c, half));
+                    keyboard.key_pressed(keyboard::key_code_to_key(KeyCode::Char(c)));
+                    let c = if config.ignore_case {
+                        fold_case(c, test_word.next_expected())
+                    } else {
+                        c
+                    };
+                    composer.feed(c, test_word.next_expected())
+                }
+                KeyCode::Enter if config.delimiter == Delimiter::Newline || config.multiline => {
+                    vec!['\n']
+                }
+                _ => vec![],
+            };
+            for c in chars {
+                let delimiter_duration = if c == word_delimiter && test_word.is_complete() {
+                    Some(start_char.elapsed()?)
+                } else {
+                    let duration = start_char.elapsed()?;
+                    test_word.add_char(c, duration);
+                    start_char = SystemTime::now();
+                    // Without a space to type the word's last keystroke stands
+                    // in for it, so words are timed as though it were typed
+                    // just as quickly.
+                    if config.no_space && test_word.is_complete() {
+                        Some(duration)
+                    } else {
+                        None
+                    }
+                };
+
+                if let Some(duration) = delimiter_duration {
+                    typed.push_str(test_word.as_str());
+                    typed.push(word_delimiter);
+                    typed_chars += test_word.as_str().chars().count() + 1;
+
+                    let finished_word = mem::replace(&mut test_word, Word::from(""))
+                        .finalise(word_delimiter, duration);
+                    if warmup > 0 {
+                        warmup -= 1;
+                        warmup_chars = typed_chars;
+                    } else {
+                        finished_words.push(finished_word);
+                    }
+
+                    test_word = match next_word(&mut test_words, refill) {
+                        Some(word) => {
+                            start_char = SystemTime::now();
+                            let (word, delimiter) =
+                                split_delimiter(word, config.delimiter.as_char());
+                            word_delimiter = delimiter;
+                            word.into()
+                        }
+                        None => break 'test,
+                    };
+                }
+            }
+        }
+    }
+
+    // Quitting partway through a word mustn't lose what was typed of it. When
+    // the test runs out of words this is the empty placeholder left behind by
+    // the last one.
+    if let Some(finished_word) = test_word.abandon().filter(|_| warmup == 0) {
+        finished_words.push(finished_word);
+    }
+
+    // A timed test had as many words as there was time to type.
+    let settings = config.settings(match mode {
+        TestMode::WordCount(words) => words,
+        TestMode::Timed(_) => finished_words.len(),
+    });
+
+    TestResults {
+        version: RESULTS_VERSION,
+        words: finished_words,
+        pasted,
+        settings: Some(settings),
+        skip_first_keystroke: config.skip_first_keystroke,
+        interrupted,
+    }
+}
+
+/// Add `modifier` to the style of the character `idx` characters into `text`,
+/// splitting up the span it's in as needed.
+fn modify_char(text: Vec<Text>, idx: usize, modifier: Modifier) -> Vec<Text> {
+    let mut modified = Vec::with_capacity(text.len() + 2);
+    let mut offset = 0;
+
+    for span in text {
+        let (content, style) = match span {
+            Text::Raw(content) => (content, Style::default()),
+            Text::Styled(content, style) => (content, style),
+        };
+        let len = content.chars().count();
+
+        if (offset..offset + len).contains(&idx) {
+            let split = idx - offset;
+            let before = content.chars().take(split).collect::<String>();
+            let target = content.chars().skip(split).take(1).collect::<String>();
+            let after = content.chars().skip(split + 1).collect::<String>();
+
+            if !before.is_empty() {
+                modified.push(Text::styled(before, style));
+            }
+            modified.push(Text::styled(
+                target,
+                style.modifier(style.modifier | modifier),
+            ));
+            if !after.is_empty() {
+                modified.push(Text::styled(after, style));
+            }
+        } else {
+            modified.push(Text::Styled(content, style));
+        }
+
+        offset += len;
+    }
+
+    modified
+}
+
+/// Words of multi-line text that end in a newline are the last on their line,
+/// and are followed by Enter rather than the usual `delimiter`. Returns the
+/// word along with whichever it's followed by.
+fn split_delimiter(word: &str, delimiter: char) -> (&str, char) {
+    match word.strip_suffix('\n') {
+        Some(word) => (word, '\n'),
+        None => (word, delimiter),
+    }
+}
+
+/// The upcoming words, each preceded by the delimiter of the word before it,
+/// starting with `word_delimiter` after the current word. Characters typed past
+/// the end of the current word take the place of the preview after it so that
+/// the rest of the line doesn't shift along, but never past the end of the
+/// line.
+fn preview_text(
+    test_words: &VecDeque<&str>,
+    delimiter: char,
+    word_delimiter: char,
+    overflow: usize,
+) -> String {
+    let mut preview = String::new();
+    let mut before = word_delimiter;
+    for word in test_words {
+        let (word, after) = split_delimiter(word, delimiter);
+        preview.push(before);
+        preview.push_str(word);
+        before = after;
+    }
+    let skip = preview
+        .chars()
+        .take(overflow)
+        .take_while(|c| *c != '\n')
+        .count();
+
+    preview.chars().skip(skip).collect()
+}
+
+/// Put the current word (followed by a preview of the words after it) above
+/// what's been typed of it, so the two line up character by character. Returns
+/// the text along with where the cursor goes in it.
+fn stacked_text<'t>(
+    test_word: &'t Word,
+    test_words: &VecDeque<&'t str>,
+    theme: &Theme,
+    options: RenderOptions,
+) -> (Vec<Text<'t>>, usize) {
+    let mut text = vec![Text::styled(
+        test_word.as_str(),
+        theme.active(theme.untyped),
+    )];
+    for word in test_words {
+        let (word, _) = split_delimiter(word, ' ');
+        text.push(Text::styled(format!(" {}", word), theme.preview));
+    }
+    let target_len = text
+        .iter()
+        .map(|span| match span {
+            Text::Raw(content) | Text::Styled(content, _) => content.chars().count(),
+        })
+        .sum::<usize>();
+
+    text.push(Text::raw("\n"));
+    text.extend(test_word.attempt_text(theme, options));
+    // Somewhere for the cursor to sit once the typing catches up.
+    text.push(Text::raw(" "));
+
+    (text, target_len + 1 + test_word.typed_len())
+}
+
+/// Lay the text out into lines ourselves, rather than having the paragraph wrap
+/// it, so that we know which line the current word is on and can keep that line
+/// in the middle of the screen. Returns the text along with that line, and
+/// where in `typed` each of the lines that starts with it does, so that lines
+/// scrolled off the top can be left out next time.
+#[allow(clippy::too_many_arguments)]
+fn pinned_text<'t>(
+    typed: &'t str,
+    test_word: &'t Word,
+    test_words: &VecDeque<&'t str>,
+    theme: &Theme,
+    options: RenderOptions,
+    delimiter: char,
+    word_delimiter: char,
+    width: u16,
+) -> (Vec<Text<'t>>, usize, Vec<usize>) {
+    // Each segment is a word, how wide it is, whether a new line follows it,
+    // and for the typed words where in `typed` it starts.
+    let mut offset = 0;
+    let history = typed
+        .split_inclusive([' ', '\n'])
+        .map(|word| {
+            let start = offset;
+            offset += word.len();
+            match word.strip_suffix('\n') {
+                Some(word) => (word, true, start),
+                None => (word.trim_end_matches(' '), false, start),
+            }
+        })
+        .filter(|(word, _, _)| !word.is_empty())
+        .map(|(word, newline, start)| {
+            (
+                vec![Text::styled(word, theme.typed)],
+                word.chars().count(),
+                newline,
+                Some(start),
+            )
+        })
+        .collect::<Vec<_>>();
+    let current = (
+        test_word.styled_text(theme, options),
+        test_word.len().max(test_word.typed_len()),
+        word_delimiter == '\n',
+        None,
+    );
+    let upcoming = test_words.iter().map(|word| {
+        let (word, after) = split_delimiter(word, delimiter);
+        (
+            vec![Text::styled(word, theme.preview)],
+            word.chars().count(),
+            after == '\n',
+            None,
+        )
+    });
+
+    let history_len = history.len();
+    let segments = history
+        .into_iter()
+        .chain(std::iter::once(current))
+        .chain(upcoming)
+        .enumerate();
+
+    let mut text = vec![];
+    let mut line = 0;
+    let mut line_width = 0;
+    let mut current_line = 0;
+    let mut line_starts = vec![];
+    let mut newline = false;
+
+    for (idx, (spans, segment_width, newline_after, start)) in segments {
+        let breaks = newline || line_width + 1 + segment_width > usize::from(width);
+        newline = newline_after;
+        if line_width > 0 && breaks {
+            text.push(Text::raw("\n"));
+            line += 1;
+            line_width = 0;
+        } else if line_width > 0 {
+            text.push(Text::raw(" "));
+            line_width += 1;
+        }
+
+        if idx == history_len {
+            current_line = line;
+        }
+        if let Some(start) = start.filter(|_| line_width == 0) {
+            line_starts.push(start);
+        }
+
+        text.extend(spans);
+        line_width += segment_width;
+    }
+
+    (text, current_line, line_starts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wordlist::Difficulty;
+
+    fn finished_word<'a>(value: &'a str, millis: &[u64]) -> FinishedWord<'a> {
+        let mut word = Word::from(value);
+        let (delimiter, chars) = millis.split_last().unwrap();
+        for (c, ms) in value.chars().zip(chars) {
+            word.add_char(c, Duration::from_millis(*ms));
+        }
+        word.finalise(' ', Duration::from_millis(*delimiter))
+    }
+
+    #[test]
+    fn test_pinned_text_tracks_current_line() {
+        let test_word = Word::from("cccc");
+        let test_words = VecDeque::from(vec!["dddd", "ee"]);
+
+        let (text, current_line, _) = pinned_text(
+            "aaaa bbbb ",
+            &test_word,
+            &test_words,
+            &Theme::default(),
+            RenderOptions::default(),
+            ' ',
+            ' ',
+            10,
+        );
+
+        let newlines = text
+            .iter()
+            .filter(|span| matches!(span, Text::Raw(raw) if raw == "\n"))
+            .count();
+        assert_eq!(newlines, 2);
+        assert_eq!(current_line, 1);
+    }
+
+    #[test]
+    fn test_pinned_text_line_starts() {
+        let test_word = Word::from("dd");
+        let test_words = VecDeque::from(vec!["ee"]);
+        let layout = |typed| {
+            let (text, current_line, line_starts) = pinned_text(
+                typed,
+                &test_word,
+                &test_words,
+                &Theme::default(),
+                RenderOptions::default(),
+                ' ',
+                ' ',
+                10,
+            );
+            let laid_out = text
+                .iter()
+                .map(|span| match span {
+                    Text::Raw(content) | Text::Styled(content, _) => content.to_string(),
+                })
+                .collect::<String>();
+            (laid_out, current_line, line_starts)
+        };
+
+        let typed = "aaaa bbbb cccc ";
+        let (laid_out, current_line, line_starts) = layout(typed);
+        assert_eq!(laid_out, "aaaa bbbb\ncccc dd ee");
+        assert_eq!(current_line, 1);
+        assert_eq!(line_starts, vec![0, 10]);
+
+        // Leaving out the first line lays the rest out the same.
+        let (laid_out, current_line, _) = layout(&typed[line_starts[1]..]);
+        assert_eq!(laid_out, "cccc dd ee");
+        assert_eq!(current_line, 0);
+    }
+
+    #[test]
+    fn test_preview_text_newline_delimiter() {
+        let test_words = VecDeque::from(vec!["fn", "let"]);
+
+        assert_eq!(preview_text(&test_words, ' ', ' ', 0), " fn let");
+        assert_eq!(preview_text(&test_words, ' ', ' ', 2), "n let");
+        assert_eq!(preview_text(&test_words, '\n', '\n', 0), "\nfn\nlet");
+        // Overflow stays on the current line rather than eating the next one.
+        assert_eq!(preview_text(&test_words, '\n', '\n', 2), "\nfn\nlet");
+    }
+
+    #[test]
+    fn test_multiline_preview_and_layout() {
+        let test_words = VecDeque::from(vec!["b\n", "c", "d"]);
+
+        // The current word ends its line, as does the one after it.
+        assert_eq!(preview_text(&test_words, ' ', '\n', 0), "\nb\nc d");
+
+        let test_word = Word::from("a");
+        let (text, current_line, _) = pinned_text(
+            "x y\n",
+            &test_word,
+            &test_words,
+            &Theme::default(),
+            RenderOptions::default(),
+            ' ',
+            '\n',
+            80,
+        );
+        let laid_out = text
+            .iter()
+            .map(|span| match span {
+                Text::Raw(content) | Text::Styled(content, _) => content.to_string(),
+            })
+            .collect::<String>();
+        assert_eq!(laid_out, "x y\na\nb\nc d");
+        assert_eq!(current_line, 1);
+    }
+
+    #[test]
+    fn test_pinned_text_newline_delimiter() {
+        let test_word = Word::from("cc");
+        let test_words = VecDeque::from(vec!["d"]);
+
+        let (text, current_line, _) = pinned_text(
+            "a\nb\n",
+            &test_word,
+            &test_words,
+            &Theme::default(),
+            RenderOptions::default(),
+            '\n',
+            '\n',
+            80,
+        );
+
+        let newlines = text
+            .iter()
+            .filter(|span| matches!(span, Text::Raw(raw) if raw == "\n"))
+            .count();
+        assert_eq!(newlines, 3);
+        assert_eq!(current_line, 2);
+    }
+
+    #[test]
+    fn test_paste_detector() {
+        let mut detector = PasteDetector::default();
+        let typing = Duration::from_millis(120);
+        let pasting = Duration::from_micros(100);
+
+        assert!(!detector.keystroke(typing));
+        for _ in 1..PASTE_BURST {
+            assert!(!detector.keystroke(pasting));
+        }
+        // A human pause resets the burst.
+        assert!(!detector.keystroke(typing));
+        for _ in 1..PASTE_BURST {
+            assert!(!detector.keystroke(pasting));
+        }
+        assert!(detector.keystroke(pasting));
+    }
+
+    #[test]
+    fn test_modify_char() {
+        let text = vec![Text::raw("ab"), Text::raw("cde")];
+
+        let modified = modify_char(text, 3, Modifier::UNDERLINED);
+
+        let underlined = Style::default().modifier(Modifier::UNDERLINED);
+        assert_eq!(
+            modified,
+            vec![
+                Text::styled("ab", Style::default()),
+                Text::styled("c", Style::default()),
+                Text::styled("d", underlined),
+                Text::styled("e", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_avg_word_initiation() {
+        let results = TestResults::from(vec![
+            finished_word("ab", &[300, 100, 100]),
+            finished_word("c", &[500, 100]),
+        ]);
+
+        assert_eq!(results.avg_word_initiation(), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_trigram_latencies_span_words() {
+        let results = TestResults::from(vec![
+            finished_word("ab", &[100, 200, 300]),
+            finished_word("c", &[400, 500]),
+        ]);
+
+        let latencies = results.trigram_latencies();
+
+        assert_eq!(latencies.len(), 3);
+        assert_eq!(latencies[&('a', 'b', ' ')], Duration::from_millis(300));
+        assert_eq!(latencies[&('b', ' ', 'c')], Duration::from_millis(400));
+        assert_eq!(latencies[&(' ', 'c', ' ')], Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_slowest_trigrams() {
+        let results = TestResults::from(vec![
+            finished_word("ab", &[100, 200, 900]),
+            finished_word("ab", &[100, 100, 100]),
+        ]);
+
+        let slowest = results.slowest_trigrams(2);
+
+        assert_eq!(
+            slowest,
+            vec![
+                (('a', 'b', ' '), Duration::from_millis(500)),
+                ((' ', 'a', 'b'), Duration::from_millis(100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typo_confusions_merge() {
+        let test_results = || {
+            let mut word = Word::from("ab");
+            word.add_char('o', Duration::from_millis(100));
+            word.remove_char(Duration::ZERO);
+            word.add_char('a', Duration::from_millis(100));
+            word.add_char('x', Duration::from_millis(100));
+            word.remove_char(Duration::ZERO);
+            word.add_char('b', Duration::from_millis(100));
+            TestResults::from(vec![word.finalise(' ', Duration::from_millis(100))])
+        };
+        let results = test_results()
+            .into_owned()
+            .merge(test_results().into_owned());
+
+        let total = results.typo_confusions();
+
+        assert_eq!(total.len(), 2);
+        assert_eq!(total[&('a', 'o')], 2);
+        assert_eq!(total[&('b', 'x')], 2);
+    }
+
+    #[test]
+    fn test_shifted_accuracy() {
+        let mut word = Word::from("a!");
+        word.add_char('a', Duration::from_millis(100));
+        word.add_char('1', Duration::from_millis(100));
+        word.remove_char(Duration::ZERO);
+        word.add_char('!', Duration::from_millis(100));
+        let results = TestResults::from(vec![word.finalise(' ', Duration::from_millis(100))]);
+
+        assert_eq!(results.shifted_accuracy(NumberRow::Digits), Some(50.0));
+        assert_eq!(
+            TestResults::from(vec![finished_word("ab", &[100, 100, 100])])
+                .shifted_accuracy(NumberRow::Digits),
+            None
+        );
+    }
+
+    #[test]
+    fn test_raw_and_net_wpm() {
+        let second = Duration::from_secs(1);
+        // Typed on from the typo before going back for it.
+        let sloppy = || {
+            let mut word = Word::from("ab");
+            word.add_char('x', second);
+            word.add_char('b', second);
+            word.remove_char(second);
+            word.remove_char(second);
+            word.add_char('a', second);
+            word.add_char('b', second);
+            word.finalise(' ', second)
+        };
+        // Took the typo straight back.
+        let mut word = Word::from("ab");
+        word.add_char('x', second);
+        word.remove_char(second);
+        word.add_char('a', second);
+        word.add_char('b', second);
+        let tidy = word.finalise(' ', second);
+
+        let results = TestResults::from(vec![sloppy()]);
+        assert!((results.raw_wpm() - 5.0 / 5.0 / (7.0 / 60.0)).abs() < 1e-9);
+        assert_eq!(results.net_wpm(), 0.0);
+        assert_eq!(TestResults::from(vec![]).net_wpm(), 0.0);
+
+        let results = TestResults::from(vec![tidy]);
+        assert!((results.raw_wpm() - 9.6).abs() < 1e-9);
+        assert_eq!(results.net_wpm(), results.raw_wpm());
+
+        // A skipped word's keystrokes and time count towards neither.
+        let mut word = Word::from("cd");
+        word.add_char('c', Duration::from_secs(10));
+        let results = TestResults::from(vec![
+            sloppy(),
+            word.skip(),
+            finished_word("abcdefghi", &[1000; 10]),
+        ]);
+        assert!((results.raw_wpm() - 15.0 / 5.0 / (17.0 / 60.0)).abs() < 1e-9);
+        assert!((results.net_wpm() - 10.0 / 5.0 / (17.0 / 60.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_next_word_refills() {
+        let mut test_words = VecDeque::from(vec!["a", "b"]);
+        let mut refill = || VecDeque::from(vec!["c"]);
+
+        assert_eq!(next_word(&mut test_words, &mut refill), Some("a"));
+        assert_eq!(next_word(&mut test_words, &mut refill), Some("b"));
+        assert_eq!(test_words, vec!["c"]);
+        assert_eq!(next_word(&mut VecDeque::new(), &mut VecDeque::new), None);
+    }
+
+    #[test]
+    fn test_time_breakdown() {
+        let mut word = Word::from("ab");
+        word.add_char('a', Duration::from_millis(200));
+        word.add_char('x', Duration::from_millis(3000));
+        word.remove_char(Duration::from_millis(400));
+        word.add_char('b', Duration::from_millis(200));
+        let results = TestResults::from(vec![word.finalise(' ', Duration::from_millis(200))]);
+
+        let breakdown = results.time_breakdown();
+        assert_eq!(
+            breakdown,
+            TimeBreakdown {
+                typing: Duration::from_millis(600),
+                idle: Duration::from_millis(3000),
+                correcting: Duration::from_millis(400),
+            }
+        );
+        assert_eq!(breakdown.total().as_secs_f64(), results.duration_secs());
+    }
+
+    #[test]
+    fn test_mastered() {
+        // 6 characters in 0.6s is 120 wpm.
+        let clean = || {
+            TestResults::from(vec![
+                finished_word("ab", &[100, 100, 100]),
+                finished_word("cd", &[100, 100, 100]),
+            ])
+        };
+        assert!(clean().mastered(120.0));
+        assert!(!clean().mastered(121.0));
+
+        let mut missing_words = clean();
+        missing_words.settings = Some(Settings {
+            layout: "dvorak".to_string(),
+            number_row: NumberRow::Digits,
+            words: 3,
+            delimiter: Delimiter::Space,
+            difficulty: Difficulty::Normal,
+            target_accuracy: 95.0,
+        });
+        assert!(!missing_words.mastered(20.0));
+
+        let typoed = TestResults::from(vec![typoed_word("a")]);
+        assert!(!typoed.mastered(0.0));
+    }
+
+    #[test]
+    fn test_rhythm_jitter() {
+        let steady = TestResults::from(vec![finished_word("abc", &[200, 200, 200, 900])]);
+        assert!(steady.rhythm_jitter().abs() < 1e-9);
+
+        let uneven = TestResults::from(vec![finished_word("ab", &[100, 300, 100])]);
+        assert!((uneven.rhythm_jitter() - 0.5).abs() < 1e-9);
+
+        assert_eq!(TestResults::from(vec![]).rhythm_jitter(), 0.0);
+    }
+
+    #[test]
+    fn test_row_distribution() {
+        let results = TestResults::from(vec![
+            finished_word("at", &[100, 100, 100]),
+            finished_word("p1", &[100, 100, 100]),
+        ]);
+
+        assert_eq!(results.row_distribution(), [25.0, 25.0, 50.0, 0.0]);
+        assert_eq!(TestResults::from(vec![]).row_distribution(), [0.0; 4]);
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut word = Word::from("ab");
+        word.add_char('a', Duration::from_millis(500));
+        word.add_char('x', Duration::from_millis(100));
+        word.remove_char(Duration::from_millis(100));
+        let results = TestResults::from(vec![word.skip(), finished_word("c", &[100, 100])]);
+
+        let ms = Duration::from_millis;
+        let timeline = results.timeline();
+        assert_eq!(
+            timeline,
+            vec![
+                (ms(0), 1),
+                (ms(100), 2),
+                (ms(200), 1),
+                (ms(300), 4),
+                (ms(400), 5)
+            ]
+        );
+        assert_eq!(replay_position(&timeline, ms(0)), 1);
+        assert_eq!(replay_position(&timeline, ms(250)), 1);
+        assert_eq!(replay_position(&timeline, ms(1000)), 5);
+        assert_eq!(replay_position(&vec![], ms(1000)), 0);
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case('A', Some('a')), 'a');
+        assert_eq!(fold_case('A', Some('A')), 'A');
+        assert_eq!(fold_case('A', None), 'a');
+        assert_eq!(fold_case('"', Some('\'')), '"');
+    }
+
+    #[test]
+    fn test_finger_travel() {
+        // The left index finger stretches from u over to i and back, and the
+        // right pinky goes from s up to l (a row up and a quarter key left).
+        let results = TestResults::from(vec![finished_word("iul", &[100, 100, 100, 100])]);
+
+        assert!((results.finger_travel() - (2.0 + 0.25_f64.hypot(1.0))).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lead_text() {
+        assert_eq!(lead_text(3), (" · ahead by 3".to_string(), Color::Green));
+        assert_eq!(lead_text(-2), (" · behind by 2".to_string(), Color::Red));
+        assert_eq!(lead_text(0).0, " · level");
+    }
+
+    #[test]
+    fn test_live_wpm_text() {
+        assert_eq!(live_wpm_text(0, Duration::ZERO), "0 wpm");
+        assert_eq!(live_wpm_text(3, Duration::ZERO), "0 wpm");
+        assert_eq!(live_wpm_text(50, Duration::from_secs(15)), "40 wpm");
+    }
+
+    #[test]
+    fn test_time_left_text() {
+        assert_eq!(time_left_text(Duration::from_secs(60)), " · time left 60s");
+        assert_eq!(
+            time_left_text(Duration::from_millis(41_200)),
+            " · time left 42s"
+        );
+        assert_eq!(time_left_text(Duration::from_millis(1)), " · time left 1s");
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = TestResults::from(vec![finished_word("ab", &[100, 100, 100])]);
+        let mut second = TestResults::from(vec![finished_word("cd", &[200, 200, 200])]);
+        second.pasted = true;
+
+        let merged = vec![TestResults::from(vec![]), first, second]
+            .into_iter()
+            .collect::<TestResults>();
+
+        assert_eq!(merged.word_cnt(), 2);
+        assert_eq!(merged.duration_secs(), 0.9);
+        assert!(merged.pasted());
+    }
+
+    #[test]
+    fn test_parse_session_version() {
+        let results = TestResults::from(vec![finished_word("ab", &[100, 100, 100])]);
+        let json = serde_json::to_string(&vec![results]).unwrap();
+        assert_eq!(TestResults::parse_session(&json).unwrap().len(), 1);
+
+        let old = json.replace(r#""version":2"#, r#""version":1"#);
+        assert_eq!(TestResults::parse_session(&old).unwrap().len(), 1);
+
+        let unversioned = json.replace(r#""version":2,"#, "");
+        assert_eq!(TestResults::parse_session(&unversioned).unwrap().len(), 1);
+
+        let future = json.replace(r#""version":2"#, r#""version":3"#);
+        assert!(matches!(
+            TestResults::parse_session(&future),
+            Err(Error::VersionError {
+                found: 3,
+                supported: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_session_settings() {
+        let mut results = TestResults::from(vec![finished_word("ab", &[100, 100, 100])]);
+        let settings = Settings {
+            layout: "dvorak".to_string(),
+            number_row: NumberRow::Symbols,
+            words: 1,
+            delimiter: Delimiter::Newline,
+            difficulty: Difficulty::Hard,
+            target_accuracy: 97.5,
+        };
+        results.settings = Some(settings.clone());
+        let json = serde_json::to_string(&vec![results]).unwrap();
+
+        let session = TestResults::parse_session(&json).unwrap();
+        assert_eq!(session[0].settings(), Some(&settings));
+    }
+
+    #[test]
+    fn test_hand_balance() {
+        let results = TestResults::from(vec![
+            finished_word("those", &[100, 100, 100, 100, 100, 100]),
+            finished_word("Aa", &[100, 100, 100]),
+        ]);
+
+        assert_eq!(results.hand_balance(), (4, 3));
+    }
+
+    #[test]
+    fn test_clean_word_wpm() {
+        let mut botched = Word::from("ab");
+        botched.add_char('x', Duration::from_millis(200));
+        botched.remove_char(Duration::ZERO);
+        botched.add_char('a', Duration::from_millis(200));
+        botched.add_char('b', Duration::from_millis(100));
+        let results = TestResults::from(vec![
+            finished_word("ab", &[200, 200, 200]),
+            botched.finalise(' ', Duration::from_millis(100)),
+        ]);
+
+        assert_eq!(results.per_word_wpm(), vec![60, 60]);
+        assert_eq!(results.clean_word_wpm(), vec![60]);
+    }
+
+    #[test]
+    fn test_hint_text() {
+        assert_eq!(hint_text('t'), " next: 't' — right middle, home row");
+        assert_eq!(hint_text('?'), " next: '?' — right pinky, top row");
+        assert_eq!(hint_text('é'), " next: 'é'");
+    }
+
+    #[test]
+    fn test_splash_text() {
+        let test_words = VecDeque::from(vec!["aoeu"; 100]);
+
+        assert_eq!(splash_text(&test_words, None), "100 words");
+        assert_eq!(
+            splash_text(&test_words, Some(25.0)),
+            "100 words · ~4 min at your 25 wpm"
+        );
+        assert_eq!(
+            splash_text(&test_words, Some(200.0)),
+            "100 words · ~30 s at your 200 wpm"
+        );
+    }
+
+    #[test]
+    fn test_cps() {
+        let mut word = Word::from("ab");
+        word.add_char('x', Duration::from_millis(250));
+        word.remove_char(Duration::ZERO);
+        word.add_char('a', Duration::from_millis(250));
+        word.add_char('b', Duration::from_millis(250));
+        let results = TestResults::from(vec![word.finalise(' ', Duration::from_millis(250))]);
+
+        assert_eq!(results.cps(), 3.0);
+        assert_eq!(TestResults::from(vec![]).cps(), 0.0);
+    }
+
+    #[test]
+    fn test_stacked_text_aligns_attempt() {
+        let mut test_word = Word::from("those");
+        test_word.add_char('t', Duration::from_millis(100));
+        test_word.add_char('j', Duration::from_millis(100));
+        let test_words = VecDeque::from(vec!["are"]);
+
+        let (text, cursor) = stacked_text(
+            &test_word,
+            &test_words,
+            &Theme::default(),
+            RenderOptions::default(),
+        );
+
+        let theme = Theme::default();
+        assert_eq!(
+            text,
+            vec![
+                Text::styled("those", theme.active(theme.untyped)),
+                Text::styled(" are", theme.preview),
+                Text::raw("\n"),
+                Text::styled("t", theme.active(theme.correct)),
+                Text::styled("j", theme.active(theme.incorrect)),
+                Text::raw(" "),
+            ]
+        );
+        assert_eq!(cursor, 12);
+    }
+
+    #[test]
+    fn test_latency_by_position() {
+        let results = TestResults::from(vec![
+            finished_word("ab", &[300, 100, 50]),
+            finished_word("abc", &[100, 300, 200, 50]),
+        ]);
+
+        assert_eq!(
+            results.latency_by_position(),
+            vec![
+                Duration::from_millis(200),
+                Duration::from_millis(200),
+                Duration::from_millis(200),
+            ]
+        );
+    }
+
+    fn typoed_word(value: &str) -> FinishedWord<'_> {
+        let mut word = Word::from(value);
+        word.add_char('x', Duration::from_millis(100));
+        word.remove_char(Duration::ZERO);
+        for c in value.chars() {
+            word.add_char(c, Duration::from_millis(100));
+        }
+        word.finalise(' ', Duration::from_millis(100))
+    }
+
+    #[test]
+    fn test_longest_clean_word_streak() {
+        let results = TestResults::from(vec![
+            finished_word("a", &[100, 100]),
+            typoed_word("b"),
+            finished_word("c", &[100, 100]),
+            finished_word("d", &[100, 100]),
+            finished_word("e", &[100, 100]),
+            typoed_word("f"),
+            finished_word("g", &[100, 100]),
+        ]);
+
+        assert_eq!(results.longest_clean_word_streak(), 3);
+    }
+
+    #[test]
+    fn test_longest_clean_word_streak_edges() {
+        assert_eq!(TestResults::from(vec![]).longest_clean_word_streak(), 0);
+        assert_eq!(
+            TestResults::from(vec![typoed_word("a"), typoed_word("b")]).longest_clean_word_streak(),
+            0
+        );
+        assert_eq!(
+            TestResults::from(vec![typoed_word("a"), finished_word("b", &[100, 100])])
+                .longest_clean_word_streak(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_skipped_words_typed_no_characters() {
+        let mut skipped = Word::from("abc");
+        skipped.add_char('a', Duration::from_millis(200));
+        let results =
+            TestResults::from(vec![finished_word("ab", &[200, 200, 200]), skipped.skip()]);
+
+        assert_eq!(results.char_cnt(), 3);
+        assert_eq!(results.per_word_wpm(), vec![60, 0]);
+        assert_eq!(results.longest_clean_word_streak(), 1);
+    }
+
+    #[test]
+    fn test_skipped_word_time_left_out_of_wpm() {
+        let mut skipped = Word::from("abc");
+        skipped.add_char('a', Duration::from_secs(10));
+        let results =
+            TestResults::from(vec![finished_word("ab", &[200, 200, 200]), skipped.skip()]);
+
+        assert!((results.wpm_avg() - 60.0).abs() < 1e-9);
+        assert!((results.duration_secs() - 10.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_length_errors() {
+        let mut overflowed = Word::from("ab");
+        for c in "abc".chars() {
+            overflowed.add_char(c, Duration::from_millis(100));
+        }
+        overflowed.remove_char(Duration::ZERO);
+        let mut dropped = Word::from("abc");
+        dropped.add_char('a', Duration::from_millis(100));
+        let results = TestResults::from(vec![
+            finished_word("ab", &[100, 100, 100]),
+            overflowed.finalise(' ', Duration::from_millis(100)),
+            dropped.skip(),
+        ]);
+
+        assert_eq!(results.length_errors(), (1, 1));
+    }
+
+    #[test]
+    fn test_double_letter_stats() {
+        let mut botched = Word::from("all");
+        botched.add_char('a', Duration::from_millis(100));
+        botched.add_char('l', Duration::from_millis(100));
+        botched.add_char('k', Duration::from_millis(300));
+        botched.remove_char(Duration::ZERO);
+        botched.add_char('l', Duration::from_millis(200));
+        let results = TestResults::from(vec![
+            finished_word("see", &[100, 100, 100, 100]),
+            botched.finalise(' ', Duration::from_millis(100)),
+        ]);
+
+        // The second e, then the k and l typed for the second l.
+        let (accuracy, latency) = results.double_letter_stats().unwrap();
+        assert_eq!(accuracy.round(), 67.0);
+        assert_eq!(latency, Duration::from_millis(200));
+
+        let results = TestResults::from(vec![finished_word("ab", &[100, 100, 100])]);
+        assert_eq!(results.double_letter_stats(), None);
+    }
+
+    #[test]
+    fn test_abandoned_word_counts() {
+        let mut abandoned = Word::from("abc");
+        abandoned.add_char('a', Duration::from_millis(100));
+        abandoned.add_char('x', Duration::from_millis(100));
+        let results = TestResults::from(vec![
+            finished_word("ab", &[100, 100, 100]),
+            abandoned.abandon().unwrap(),
+        ]);
+
+        assert_eq!(results.typo_cnt(), 1);
+        assert_eq!(results.total_keystrokes(), 3 + 2 + 1);
+        assert_eq!(results.duration_secs(), 0.5);
+    }
+
+    #[test]
+    fn test_skip_first_keystroke() {
+        let mut results = TestResults::from(vec![
+            finished_word("ab", &[3000, 500, 500]),
+            finished_word("cd", &[500, 500, 500]),
+        ]);
+        assert_eq!(results.first_keystroke(), Duration::from_millis(3000));
+        assert_eq!(results.duration_secs(), 5.5);
+
+        results.skip_first_keystroke = true;
+        assert_eq!(results.duration_secs(), 2.5);
+        assert_eq!(results.wpm_avg(), 5.0 / 5.0 / (2.5 / 60.0));
+    }
+
+    #[test]
+    fn test_typo_clusters() {
+        let mut panicked = Word::from("abc");
+        panicked.add_char('x', Duration::from_millis(100));
+        panicked.remove_char(Duration::ZERO);
+        panicked.add_char('y', Duration::from_millis(100));
+        panicked.add_char('z', Duration::from_millis(100));
+        panicked.remove_char(Duration::ZERO);
+        panicked.remove_char(Duration::ZERO);
+        panicked.add_char('a', Duration::from_millis(100));
+        let mut trailing = Word::from("de");
+        trailing.add_char('x', Duration::from_millis(100));
+        let results = TestResults::from(vec![
+            typoed_word("ab"),
+            panicked.skip(),
+            finished_word("c", &[100, 100]),
+            trailing.skip(),
+        ]);
+
+        assert_eq!(results.typo_clusters(), vec![1, 3, 1]);
+        assert_eq!(results.max_typo_streak(), 3);
+        assert_eq!(TestResults::from(vec![]).max_typo_streak(), 0);
+    }
+
+    #[test]
+    fn test_total_keystrokes() {
+        let mut dropped = Word::from("abc");
+        dropped.add_char('a', Duration::from_millis(100));
+        let results = TestResults::from(vec![
+            finished_word("ab", &[100, 100, 100]),
+            typoed_word("cd"),
+            dropped.skip(),
+        ]);
+
+        // ab␣ + x⌫cd␣ + a⎋
+        assert_eq!(results.total_keystrokes(), 3 + 5 + 2);
+    }
+
+    #[test]
+    fn test_worst_words() {
+        let results = TestResults::from(vec![
+            finished_word("fast", &[50, 50, 50, 50, 50]),
+            finished_word("slow", &[500, 500, 500, 500, 500]),
+            typoed_word("typo"),
+            finished_word("slow", &[500, 500, 500, 500, 500]),
+        ]);
+
+        assert_eq!(results.worst_words(2), vec!["typo", "slow"]);
+        assert_eq!(results.worst_words(5), vec!["typo", "slow", "fast"]);
+    }
+
+    #[test]
+    fn test_median_word_duration_secs() {
+        let fast = || finished_word("a", &[100, 100]);
+        let slow = finished_word("a", &[5000, 1000]);
+
+        assert_eq!(
+            TestResults::from(vec![fast(), slow, fast()]).median_word_duration_secs(),
+            0.2
+        );
+        assert_eq!(
+            TestResults::from(vec![fast(), finished_word("a", &[200, 400])])
+                .median_word_duration_secs(),
+            0.4
+        );
+        assert_eq!(TestResults::from(vec![]).median_word_duration_secs(), 0.0);
+    }
+
+    #[test]
+    fn test_consistency() {
+        let word = |ms| finished_word("a", &[ms, ms]);
+
+        assert_eq!(
+            TestResults::from(vec![word(100), word(100)]).consistency(),
+            100.0
+        );
+        assert_eq!(
+            TestResults::from(vec![word(100), word(300)]).consistency(),
+            50.0
+        );
+        let bursty = TestResults::from(vec![word(100), word(900)]);
+        assert!((bursty.consistency() - 20.0).abs() < 1e-9);
+        assert_eq!(
+            TestResults::from(vec![word(10), word(10), word(10), word(5000)]).consistency(),
+            0.0
+        );
+        assert_eq!(TestResults::from(vec![word(100)]).consistency(), 100.0);
+        assert_eq!(TestResults::from(vec![]).consistency(), 100.0);
+    }
+
+    #[test]
+    fn test_typo_counts_by_expected() {
+        let results = TestResults::from(vec![
+            typoed_word("ba"),
+            typoed_word("ab"),
+            typoed_word("aa"),
+        ]);
+
+        assert_eq!(results.typo_counts_by_expected(), vec![('a', 2), ('b', 1)]);
+        assert!(TestResults::from(vec![])
+            .typo_counts_by_expected()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let results = TestResults::from(vec![typoed_word("abc")]);
+
+        assert_eq!(results.accuracy(), 75.0);
+        assert_eq!(TestResults::from(vec![]).accuracy(), 100.0);
+    }
+
+    #[test]
+    fn test_key_stats() {
+        let results = TestResults::from(vec![typoed_word("Aa")]);
+
+        assert_eq!(
+            results.key_stats(),
+            vec![('a', (3, 1))].into_iter().collect()
+        );
+    }
 }
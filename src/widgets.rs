@@ -0,0 +1,230 @@
+use std::cmp::min;
+
+use tui::{
+    backend::Backend,
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    symbols::{bar, line},
+    widgets::{Block, Paragraph, Text, Widget},
+    Frame,
+};
+
+/// Check that the frame is at least `width` by `height`, and if it isn't then
+/// say so instead of drawing a view that would be squashed beyond reading.
+pub(crate) fn fits<B: Backend>(frame: &mut Frame<B>, width: u16, height: u16) -> bool {
+    let size = frame.size();
+    if size.width >= width && size.height >= height {
+        return true;
+    }
+
+    let text = [Text::raw(format!(
+        "terminal too small, needs {}×{} but is {}×{}",
+        width, height, size.width, size.height
+    ))];
+    frame.render_widget(Paragraph::new(text.iter()).wrap(true), size);
+
+    false
+}
+
+/// A sparkline with a horizontal reference line drawn at `goal`, for seeing at
+/// a glance which values fell short of it.
+pub(crate) struct GoalSparkline<'a> {
+    block: Option<Block<'a>>,
+    data: &'a [u64],
+    widths: Option<&'a [u64]>,
+    goal: Option<u64>,
+    style: Style,
+    below_style: Style,
+    goal_style: Style,
+}
+
+impl<'a> GoalSparkline<'a> {
+    pub fn new(data: &'a [u64]) -> Self {
+        Self {
+            block: None,
+            data,
+            widths: None,
+            goal: None,
+            style: Style::default(),
+            below_style: Style::default(),
+            goal_style: Style::default(),
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Stretch each bar across the whole area in proportion to its width in
+    /// `widths`, instead of giving one column to each, so that the horizontal
+    /// axis can be something like time.
+    pub fn widths(mut self, widths: Option<&'a [u64]>) -> Self {
+        self.widths = widths;
+        self
+    }
+
+    /// The value drawn in each of `width` columns.
+    fn columns(&self, width: u16) -> Vec<u64> {
+        let widths = match self.widths {
+            Some(widths) => widths,
+            None => return self.data.iter().take(width as usize).copied().collect(),
+        };
+        let total = widths.iter().sum::<u64>();
+        if total == 0 {
+            return vec![];
+        }
+
+        // Each column shows whichever bar its middle falls in.
+        let width = u64::from(width);
+        let mut bars = self.data.iter().zip(widths).scan(0, |end, (value, width)| {
+            *end += width;
+            Some((*value, *end))
+        });
+        let mut bar = bars.next();
+        (0..width)
+            .filter_map(|column| {
+                let middle = (column * 2 + 1) * total / (width * 2);
+                while bar.is_some_and(|(_, end)| end <= middle) {
+                    bar = bars.next();
+                }
+                bar.map(|(value, _)| value)
+            })
+            .collect()
+    }
+
+    pub fn goal(mut self, goal: Option<u64>) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    /// The style of bars that didn't reach the goal.
+    pub fn below_style(mut self, style: Style) -> Self {
+        self.below_style = style;
+        self
+    }
+
+    pub fn goal_style(mut self, style: Style) -> Self {
+        self.goal_style = style;
+        self
+    }
+}
+
+impl Widget for GoalSparkline<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let spark_area = match self.block {
+            Some(ref mut block) => {
+                block.render(area, buf);
+                block.inner(area)
+            }
+            None => area,
+        };
+
+        if spark_area.height < 1 {
+            return;
+        }
+
+        let height = u64::from(spark_area.height);
+        let max = self
+            .data
+            .iter()
+            .copied()
+            .chain(self.goal)
+            .max()
+            .unwrap_or(1)
+            .max(1);
+
+        // The row the goal line sits on, counted up from the bottom.
+        let goal_row = self
+            .goal
+            .map(|goal| min((goal * height).saturating_sub(1) / max, height - 1));
+
+        let columns = self.columns(spark_area.width);
+        for (i, value) in columns.iter().enumerate() {
+            let style = match self.goal {
+                Some(goal) if *value < goal => self.below_style,
+                _ => self.style,
+            };
+
+            let mut eighths = value * height * 8 / max;
+            for row in 0..spark_area.height {
+                let symbol = match eighths {
+                    0 => bar::NINE_LEVELS.empty,
+                    1 => bar::NINE_LEVELS.one_eighth,
+                    2 => bar::NINE_LEVELS.one_quarter,
+                    3 => bar::NINE_LEVELS.three_eighths,
+                    4 => bar::NINE_LEVELS.half,
+                    5 => bar::NINE_LEVELS.five_eighths,
+                    6 => bar::NINE_LEVELS.three_quarters,
+                    7 => bar::NINE_LEVELS.seven_eighths,
+                    _ => bar::NINE_LEVELS.full,
+                };
+                eighths = eighths.saturating_sub(8);
+
+                let cell = buf.get_mut(spark_area.left() + i as u16, spark_area.bottom() - 1 - row);
+                if symbol == bar::NINE_LEVELS.empty && goal_row == Some(u64::from(row)) {
+                    cell.set_symbol(line::HORIZONTAL).set_style(self.goal_style);
+                } else {
+                    cell.set_symbol(symbol).set_style(style);
+                }
+            }
+        }
+
+        // Carry the goal line on past the end of the data.
+        if let Some(row) = goal_row {
+            let y = spark_area.bottom() - 1 - row as u16;
+            for x in spark_area.left() + columns.len() as u16..spark_area.right() {
+                buf.get_mut(x, y)
+                    .set_symbol(line::HORIZONTAL)
+                    .set_style(self.goal_style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_fits() {
+        let mut terminal = Terminal::new(TestBackend::new(12, 2)).unwrap();
+        let mut fitted = None;
+        terminal
+            .draw(|mut frame| fitted = Some(fits(&mut frame, 10, 4)))
+            .unwrap();
+
+        assert_eq!(fitted, Some(false));
+        assert_eq!(
+            *terminal.backend().buffer(),
+            Buffer::with_lines(vec!["terminal too", "small, needs"])
+        );
+    }
+
+    #[test]
+    fn test_goal_line_drawn_over_empty_cells() {
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+
+        GoalSparkline::new(&[8, 2])
+            .goal(Some(4))
+            .render(area, &mut buf);
+
+        assert_eq!(
+            buf,
+            Buffer::with_lines(vec!["█   ", "█   ", "█───", "██  "])
+        );
+    }
+
+    #[test]
+    fn test_widths() {
+        let sparkline = GoalSparkline::new(&[8, 2, 4]);
+        assert_eq!(sparkline.columns(2), vec![8, 2]);
+
+        let sparkline = sparkline.widths(Some(&[1, 2, 1]));
+        assert_eq!(sparkline.columns(8), vec![8, 8, 2, 2, 2, 2, 4, 4]);
+        assert_eq!(sparkline.columns(4), vec![8, 2, 2, 4]);
+    }
+}
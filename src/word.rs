@@ -1,25 +1,72 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
-use tui::{
-    style::{Color, Style},
-    widgets::Text,
-};
+use serde::{Deserialize, Serialize};
+use tui::widgets::Text;
 
-use crate::metrics::Metric;
+use crate::{metrics::Metric, theme::Theme};
+
+/// How the current word is drawn, beyond the styles the theme gives it.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct RenderOptions {
+    /// Whether to show the untyped characters as blanks, so that only the
+    /// word's length gives it away.
+    pub blank: bool,
+    /// How long a correct keystroke can take before it's shown as slow, if
+    /// they're told apart at all.
+    pub slow_after: Option<Duration>,
+}
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct Word<'a> {
     value: &'a str,
     typed: String,
+    /// How long each of the `typed` characters took.
+    typed_durations: Vec<Duration>,
     metrics: Vec<Metric>,
+    overflowed: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct FinishedWord<'a> {
-    value: &'a str,
+    #[serde(borrow)]
+    value: Cow<'a, str>,
+    typed: String,
     metrics: Vec<Metric>,
+    /// Whether the word was given up on rather than finished.
+    #[serde(default)]
+    skipped: bool,
+    /// Whether more was typed than the word has characters at any point, even
+    /// if it was deleted again.
+    #[serde(default)]
+    overflowed: bool,
+}
+
+/// How a single character of a finished word compares to what was typed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CharDiff {
+    Correct(char),
+    Substituted { expected: char, typed: char },
+    Missing(char),
+    Extra(char),
 }
 
 impl<'a> FinishedWord<'a> {
+    /// The word with its own copy of its value, so it can outlive the words
+    /// it was picked from.
+    pub fn into_owned(self) -> FinishedWord<'static> {
+        FinishedWord {
+            value: Cow::Owned(self.value.into_owned()),
+            typed: self.typed,
+            metrics: self.metrics,
+            skipped: self.skipped,
+            overflowed: self.overflowed,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
     fn len(&self) -> usize {
         self.value.chars().count()
     }
@@ -31,13 +78,73 @@ impl<'a> FinishedWord<'a> {
     pub fn metrics(&self) -> &[Metric] {
         &self.metrics
     }
+
+    /// Whether any character of the word was mistyped along the way, even if it
+    /// was corrected afterwards. Skipped words count as failed too.
+    pub fn has_typo(&self) -> bool {
+        self.skipped
+            || self
+                .metrics
+                .iter()
+                .any(|metric| matches!(metric, Metric::Typo { .. }))
+    }
+
+    pub fn skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// Whether the word was left with fewer characters typed than it has.
+    pub fn dropped(&self) -> bool {
+        self.typed.chars().count() < self.len()
+    }
+
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn delimiter(&self) -> Option<char> {
+        self.metrics.iter().rev().find_map(|metric| match metric {
+            Metric::Delimiter { value, .. } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Compare what was typed against the word, character by character.
+    pub fn diff(&self) -> Vec<CharDiff> {
+        let mut expected = self.value.chars();
+        let mut typed = self.typed.chars();
+        let mut diff = vec![];
+
+        loop {
+            let char_diff = match (expected.next(), typed.next()) {
+                (Some(e), Some(t)) if e == t => CharDiff::Correct(e),
+                (Some(e), Some(t)) => CharDiff::Substituted {
+                    expected: e,
+                    typed: t,
+                },
+                (Some(e), None) => CharDiff::Missing(e),
+                (None, Some(t)) => CharDiff::Extra(t),
+                (None, None) => break,
+            };
+            diff.push(char_diff);
+        }
+
+        diff
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.metrics.iter().map(Metric::duration).sum()
+    }
 }
 
 impl<'a> From<Word<'a>> for FinishedWord<'a> {
     fn from(word: Word<'a>) -> Self {
         Self {
-            value: word.value,
+            value: word.value.into(),
+            typed: word.typed,
             metrics: word.metrics,
+            skipped: false,
+            overflowed: word.overflowed,
         }
     }
 }
@@ -55,6 +162,16 @@ impl<'a> Word<'a> {
         self.value.chars().count()
     }
 
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// The character the next keystroke should type, or `None` once as many
+    /// characters as the word has have been typed (right or wrong).
+    pub fn next_expected(&self) -> Option<char> {
+        self.char_at(self.typed_len())
+    }
+
     pub fn typed_len(&self) -> usize {
         self.typed.chars().count()
     }
@@ -64,7 +181,7 @@ impl<'a> Word<'a> {
     }
 
     pub fn add_char(&mut self, typed: char, duration: Duration) {
-        let expected = self.char_at(self.typed.len());
+        let expected = self.next_expected();
         if let Some(expected) = expected {
             if typed != expected {
                 self.metrics.push(Metric::Typo {
@@ -78,12 +195,19 @@ impl<'a> Word<'a> {
                     duration,
                 });
             }
+        } else {
+            self.overflowed = true;
         }
         self.typed.push(typed);
+        self.typed_durations.push(duration);
     }
 
-    pub fn remove_char(&mut self) {
-        self.typed.pop();
+    /// Take back the last typed character, if there is one to take back.
+    pub fn remove_char(&mut self, duration: Duration) {
+        if self.typed.pop().is_some() {
+            self.metrics.push(Metric::Backspace { duration });
+            self.typed_durations.pop();
+        }
     }
 
     pub fn is_complete(&self) -> bool {
@@ -98,29 +222,64 @@ impl<'a> Word<'a> {
         self.into()
     }
 
-    pub fn styled_text(&self) -> Vec<Text> {
+    /// Give up on the word, finishing it as it is without a delimiter.
+    pub fn skip(self) -> FinishedWord<'a> {
+        FinishedWord {
+            skipped: true,
+            ..self.into()
+        }
+    }
+
+    /// Finish a word the test ended partway through, as skipped so that its
+    /// keystrokes still count, or `None` if nothing was typed of it.
+    pub fn abandon(self) -> Option<FinishedWord<'a>> {
+        if self.metrics.is_empty() {
+            None
+        } else {
+            Some(self.skip())
+        }
+    }
+
+    pub fn styled_text(&self, theme: &Theme, options: RenderOptions) -> Vec<Text<'_>> {
+        let mut styled = self.attempt_text(theme, options);
+
+        // Fill in the untyped characters.
+        for idx in self.typed_len()..self.len() {
+            if let Some(c) = self.char_at(idx) {
+                let c = if options.blank { '_' } else { c };
+                styled.push(Text::styled(c.to_string(), theme.active(theme.untyped)));
+            }
+        }
+
+        styled
+    }
+
+    /// Just the characters typed so far, coloured by whether they're right,
+    /// and if the `options` say when, whether they were slow.
+    pub fn attempt_text(&self, theme: &Theme, options: RenderOptions) -> Vec<Text<'_>> {
         let mut styled = vec![];
 
-        // Display the typed characters.
-        for (idx, tc) in self.typed.chars().enumerate() {
+        for (idx, (tc, duration)) in self.typed.chars().zip(&self.typed_durations).enumerate() {
             let wc = self.char_at(idx);
 
-            let color = if wc.is_none() || wc.unwrap() != tc {
-                Color::Red
+            let style = if wc.is_none() || wc.unwrap() != tc {
+                theme.incorrect
+            } else if options
+                .slow_after
+                .is_some_and(|slow_after| *duration > slow_after)
+            {
+                theme.slow
             } else {
-                Color::Blue
+                theme.correct
             };
 
-            let c = if tc == ' ' { '␣' } else { tc };
+            let c = match tc {
+                ' ' => '␣',
+                '\n' => '⏎',
+                _ => tc,
+            };
 
-            styled.push(Text::styled(c.to_string(), Style::default().fg(color)));
-        }
-
-        // Fill in the untyped characters.
-        for idx in self.typed_len()..self.len() {
-            if let Some(c) = self.char_at(idx) {
-                styled.push(Text::raw(c.to_string()));
-            }
+            styled.push(Text::styled(c.to_string(), theme.active(style)));
         }
 
         styled
@@ -134,7 +293,9 @@ impl<'a> From<&'a str> for Word<'a> {
         Self {
             value: string,
             typed,
+            typed_durations: vec![],
             metrics,
+            overflowed: false,
         }
     }
 }
@@ -149,10 +310,12 @@ mod tests {
         let expected = Word {
             value: "test",
             typed: "t".to_string(),
+            typed_durations: vec![Duration::from_secs(1)],
             metrics: vec![Metric::Match {
                 value: 't',
                 duration: Duration::from_secs(1),
             }],
+            overflowed: false,
         };
 
         word.add_char('t', Duration::from_secs(1));
@@ -165,15 +328,137 @@ mod tests {
         let expected = Word {
             value: "test",
             typed: "e".to_string(),
+            typed_durations: vec![Duration::from_secs(1)],
             metrics: vec![Metric::Typo {
                 value: 'e',
                 expected: 't',
                 duration: Duration::from_secs(1),
             }],
+            overflowed: false,
         };
 
         word.add_char('e', Duration::from_secs(1));
 
         assert_eq!(word, expected);
     }
+
+    #[test]
+    fn test_diff() {
+        let word = FinishedWord {
+            value: "naïve".into(),
+            typed: "naivety".to_string(),
+            metrics: vec![],
+            skipped: false,
+            overflowed: false,
+        };
+
+        assert_eq!(
+            word.diff(),
+            vec![
+                CharDiff::Correct('n'),
+                CharDiff::Correct('a'),
+                CharDiff::Substituted {
+                    expected: 'ï',
+                    typed: 'i'
+                },
+                CharDiff::Correct('v'),
+                CharDiff::Correct('e'),
+                CharDiff::Extra('t'),
+                CharDiff::Extra('y'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_missing() {
+        let word = FinishedWord {
+            value: "test".into(),
+            typed: "te".to_string(),
+            metrics: vec![],
+            skipped: false,
+            overflowed: false,
+        };
+
+        assert_eq!(
+            word.diff(),
+            vec![
+                CharDiff::Correct('t'),
+                CharDiff::Correct('e'),
+                CharDiff::Missing('s'),
+                CharDiff::Missing('t'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_expected() {
+        let mut word: Word = "ab".into();
+        assert_eq!(word.next_expected(), Some('a'));
+
+        word.add_char('x', Duration::from_secs(1));
+        assert_eq!(word.next_expected(), Some('b'));
+
+        word.add_char('b', Duration::from_secs(1));
+        assert_eq!(word.next_expected(), None);
+    }
+
+    #[test]
+    fn test_abandon() {
+        assert!(Word::from("test").abandon().is_none());
+
+        let mut word = Word::from("test");
+        word.add_char('t', Duration::from_secs(1));
+        word.add_char('x', Duration::from_secs(1));
+        let abandoned = word.abandon().unwrap();
+        assert!(abandoned.skipped());
+        assert_eq!(abandoned.metrics().len(), 2);
+    }
+
+    #[test]
+    fn test_backspace_on_empty_word() {
+        let mut word = Word::from("test");
+        word.remove_char(Duration::from_secs(1));
+        assert!(word.abandon().is_none());
+    }
+
+    #[test]
+    fn test_slow_attempt_text() {
+        let theme = Theme::default();
+        let options = RenderOptions {
+            slow_after: Some(Duration::from_millis(300)),
+            ..RenderOptions::default()
+        };
+        let mut word = Word::from("abc");
+        word.add_char('a', Duration::from_millis(100));
+        word.add_char('b', Duration::from_millis(500));
+        word.add_char('x', Duration::from_millis(500));
+
+        assert_eq!(
+            word.attempt_text(&theme, options),
+            vec![
+                Text::styled("a", theme.active(theme.correct)),
+                Text::styled("b", theme.active(theme.slow)),
+                Text::styled("x", theme.active(theme.incorrect)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blank_styled_text() {
+        let theme = Theme::default();
+        let options = RenderOptions {
+            blank: true,
+            ..RenderOptions::default()
+        };
+        let mut word = Word::from("ab");
+        word.add_char('a', Duration::from_millis(100));
+
+        assert_eq!(
+            word.styled_text(&theme, options),
+            vec![
+                Text::styled("a", theme.active(theme.correct)),
+                Text::styled("_", theme.active(theme.untyped)),
+            ]
+        );
+    }
 }
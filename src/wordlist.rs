@@ -0,0 +1,119 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// A few hundred common words, compiled into the binary so that it works
+/// without the full word list. They're in order of how common they are.
+const MINIMAL: &str = include_str!("../assets/words_minimal.txt");
+
+/// Common contractions, which the word lists don't have, for `--contractions`.
+pub(crate) const CONTRACTIONS: &[&str] = &[
+    "don't",
+    "doesn't",
+    "didn't",
+    "isn't",
+    "aren't",
+    "wasn't",
+    "weren't",
+    "can't",
+    "couldn't",
+    "won't",
+    "wouldn't",
+    "shouldn't",
+    "hasn't",
+    "haven't",
+    "hadn't",
+    "it's",
+    "that's",
+    "there's",
+    "here's",
+    "what's",
+    "who's",
+    "he's",
+    "she's",
+    "let's",
+    "i'm",
+    "you're",
+    "we're",
+    "they're",
+    "i've",
+    "you've",
+    "we've",
+    "they've",
+    "i'd",
+    "you'd",
+    "he'd",
+    "she'd",
+    "we'd",
+    "they'd",
+    "i'll",
+    "you'll",
+    "he'll",
+    "she'll",
+    "we'll",
+    "they'll",
+];
+
+/// How many of the most common words count as common for `--difficulty`.
+const COMMON_WORDS: usize = 200;
+
+/// How much of the time a word of the disfavoured kind is kept when it comes
+/// up, for the difficulties that favour one kind.
+const DISFAVOURED_CHANCE: f64 = 0.1;
+
+/// Whether tests lean towards common or rare words.
+///
+/// This only weighs up the words a lesson's alphabet (and `--min-len` and
+/// `--max-len`) already allow, so an early lesson with only a handful of
+/// typeable words gets mostly the same ones whatever the difficulty.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Difficulty {
+    /// Mostly common words.
+    Easy,
+    /// Words as they come, common or not.
+    Normal,
+    /// Mostly rare words, to test vocabulary and spelling.
+    Hard,
+}
+
+impl Difficulty {
+    /// The chance of keeping a word that comes up, given whether it's common.
+    pub fn keep_chance(self, common: bool) -> f64 {
+        match (self, common) {
+            (Self::Easy, false) | (Self::Hard, true) => DISFAVOURED_CHANCE,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The most common words, which every other word is rarer than.
+pub(crate) fn common() -> HashSet<&'static str> {
+    MINIMAL.split_whitespace().take(COMMON_WORDS).collect()
+}
+
+/// Where a debug build loads the full word list from.
+#[cfg(feature = "full-word-list")]
+const FULL_WORD_LIST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/words_alpha.txt");
+
+/// The built-in word list: the full `words_alpha` asset unless `minimal` is
+/// set, the binary was built without the `full-word-list` feature, or the
+/// asset can't be loaded.
+#[cfg(feature = "full-word-list")]
+pub(crate) fn builtin(minimal: bool) -> Cow<'static, str> {
+    // A debug build reads the asset from disk when it's asked for, and would
+    // panic if it's gone missing.
+    let missing = cfg!(debug_assertions) && !std::path::Path::new(FULL_WORD_LIST).is_file();
+
+    if minimal || missing {
+        MINIMAL.into()
+    } else {
+        resource::resource_str!("assets/words_alpha.txt").into()
+    }
+}
+
+#[cfg(not(feature = "full-word-list"))]
+pub(crate) fn builtin(_minimal: bool) -> Cow<'static, str> {
+    MINIMAL.into()
+}